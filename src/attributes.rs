@@ -0,0 +1,192 @@
+use crate::glob::Glob;
+
+/// The state of an attribute for a path as described in `gitattributes(5)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeState {
+    /// The attribute is set (`attr`.)
+    Set,
+    /// The attribute is unset (`-attr`.)
+    Unset,
+    /// The attribute is set to a value (`attr=value`.)
+    Value(String),
+    /// No rule says anything about the attribute (or it was reset with `!attr`.)
+    Unspecified,
+}
+
+/// The linguist attributes that take a file out of line counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinguistAttribute {
+    /// `linguist-generated`, the file is generated (such as lockfiles or protobuf output.)
+    Generated,
+    /// `linguist-vendored`, the file is third party code.
+    Vendored,
+    /// `linguist-documentation`, the file is documentation.
+    Documentation,
+}
+
+impl LinguistAttribute {
+    /// Gets the name of the attribute as written in `.gitattributes`.
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Self::Generated => "linguist-generated",
+            Self::Vendored => "linguist-vendored",
+            Self::Documentation => "linguist-documentation",
+        };
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AttributeRule {
+    /// The directory the `.gitattributes` file the rule came from is in.
+    base: String,
+    glob: Glob,
+    /// Patterns without a slash match the filename at any depth.
+    match_filename: bool,
+    attributes: Vec<(String, AttributeState)>,
+}
+
+impl AttributeRule {
+    fn is_match(&self, path: &str) -> bool {
+        let relative_path = match self.base.as_str() {
+            "" => path,
+            base => match path.strip_prefix(base).and_then(|v| v.strip_prefix('/')) {
+                Some(v) => v,
+                None => return false,
+            },
+        };
+
+        if self.match_filename {
+            let filename = relative_path.rsplit('/').next().unwrap_or(relative_path);
+            return self.glob.is_match(filename);
+        }
+        return self.glob.is_match(relative_path);
+    }
+}
+
+/// A set of rules parsed from the `.gitattributes` files of a tree.
+/// Rules added later take precedence, so files should be added from the root of the tree down.
+/// ```
+/// # use git_stats::attributes::{AttributeState, GitAttributes};
+/// let mut attributes = GitAttributes::new();
+/// attributes.add_file("", "*.png binary\n*.lock -diff\n");
+/// attributes.add_file("assets", "icon.png -binary\n");
+///
+/// assert!(attributes.is_binary("images/logo.png"));
+/// assert!(attributes.is_binary("Cargo.lock"));
+/// assert!(!attributes.is_binary("src/main.rs"));
+/// assert_eq!(attributes.get("assets/icon.png", "binary"), AttributeState::Unset);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GitAttributes {
+    rules: Vec<AttributeRule>,
+}
+
+impl GitAttributes {
+    /// Creates an empty set of attributes.
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Parses the contents of a `.gitattributes` file and adds its rules.
+    /// `base` is the directory the file is in, `""` being the root of the tree.
+    /// Lines that can't be parsed are skipped.
+    pub fn add_file(&mut self, base: &str, contents: &str) {
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let pattern = match parts.next() {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let mut attributes: Vec<(String, AttributeState)> = vec![];
+            for attribute in parts {
+                if let Some(name) = attribute.strip_prefix('-') {
+                    attributes.push((name.to_string(), AttributeState::Unset));
+                } else if let Some(name) = attribute.strip_prefix('!') {
+                    attributes.push((name.to_string(), AttributeState::Unspecified));
+                } else if let Some((name, value)) = attribute.split_once('=') {
+                    attributes.push((name.to_string(), AttributeState::Value(value.to_string())));
+                } else if attribute == "binary" {
+                    // The builtin `binary` macro
+                    attributes.push(("binary".to_string(), AttributeState::Set));
+                    attributes.push(("diff".to_string(), AttributeState::Unset));
+                    attributes.push(("merge".to_string(), AttributeState::Unset));
+                    attributes.push(("text".to_string(), AttributeState::Unset));
+                } else {
+                    attributes.push((attribute.to_string(), AttributeState::Set));
+                }
+            }
+
+            let trimmed_pattern = pattern.trim_start_matches('/');
+            let glob = match Glob::new(trimmed_pattern) {
+                Ok(v) => v,
+                Err(e) => {
+                    log::warn!("Skipping attributes pattern '{pattern}': {e}");
+                    continue;
+                },
+            };
+
+            self.rules.push(AttributeRule {
+                base: base.trim_matches('/').to_string(),
+                glob,
+                match_filename: !pattern.contains('/'),
+                attributes,
+            });
+        }
+    }
+
+    /// Gets the state of attribute `name` for `path`.
+    pub fn get(&self, path: &str, name: &str) -> AttributeState {
+        for rule in self.rules.iter().rev() {
+            let state = rule.attributes
+                .iter()
+                .rev()
+                .find(|(attribute, _)| attribute == name);
+
+            if let Some((_, state)) = state {
+                if rule.is_match(path) {
+                    return state.clone();
+                }
+            }
+        }
+        return AttributeState::Unspecified;
+    }
+
+    /// Checks if attribute `name` is set for `path`.
+    /// Linguist style `attr=true` values also count as set.
+    pub fn is_set(&self, path: &str, name: &str) -> bool {
+        return match self.get(path, name) {
+            AttributeState::Set => true,
+            AttributeState::Value(v) => v == "true",
+            _ => false,
+        };
+    }
+
+    /// Gets the first linguist attribute set for `path` which should take it out of line counts.
+    /// ```
+    /// # use git_stats::attributes::{GitAttributes, LinguistAttribute};
+    /// let mut attributes = GitAttributes::new();
+    /// attributes.add_file("", "*.pb.go linguist-generated=true\nvendor/** linguist-vendored\n");
+    ///
+    /// assert_eq!(attributes.linguist_attribute("api/user.pb.go"), Some(LinguistAttribute::Generated));
+    /// assert_eq!(attributes.linguist_attribute("vendor/lib/a.c"), Some(LinguistAttribute::Vendored));
+    /// assert_eq!(attributes.linguist_attribute("src/main.go"), None);
+    /// ```
+    pub fn linguist_attribute(&self, path: &str) -> Option<LinguistAttribute> {
+        return [
+            LinguistAttribute::Generated,
+            LinguistAttribute::Vendored,
+            LinguistAttribute::Documentation,
+        ].into_iter().find(|v| self.is_set(path, v.name()));
+    }
+
+    /// Checks if `path` is marked as binary, either with `binary` or `-diff`.
+    pub fn is_binary(&self, path: &str) -> bool {
+        return self.is_set(path, "binary") || self.get(path, "diff") == AttributeState::Unset;
+    }
+}
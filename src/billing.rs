@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::{export::csv_field, session::WorkSession};
+
+/// How the time of a session is rounded to the billing increment.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Rounding {
+    /// Always rounds up to the next increment.
+    Up,
+    /// Rounds to the nearest increment, halves rounding up.
+    Nearest,
+}
+
+/// The rules used to turn sessions into billable time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BillingRules {
+    /// The seconds sessions are rounded to, no rounding if 0.
+    pub increment: u64,
+    /// How sessions are rounded to the increment.
+    pub rounding: Rounding,
+    /// The fewest seconds a session is billed for.
+    pub minimum: u64,
+    /// The hourly rate of the sessions without a rate for their repo or client.
+    pub default_rate: f64,
+    /// The hourly rates of repos or clients, by their label or name.
+    pub rates: HashMap<String, f64>,
+    /// The clients of repos, by their label.
+    pub clients: HashMap<String, String>,
+}
+
+impl Default for BillingRules {
+    fn default() -> Self {
+        return Self {
+            increment: 15 * 60,
+            rounding: Rounding::Up,
+            minimum: 0,
+            default_rate: 0.0,
+            rates: HashMap::new(),
+            clients: HashMap::new(),
+        };
+    }
+}
+
+impl BillingRules {
+    /// Gets the seconds billed for a session that took `seconds`, at least the minimum and then
+    /// rounded to the increment.
+    /// ```
+    /// # use git_stats::billing::{BillingRules, Rounding};
+    /// let rules = BillingRules { increment: 900, minimum: 1800, ..Default::default() };
+    /// assert_eq!(rules.bill(600), 1800);
+    /// assert_eq!(rules.bill(1801), 2700);
+    ///
+    /// let rules = BillingRules { increment: 360, rounding: Rounding::Nearest, ..Default::default() };
+    /// assert_eq!(rules.bill(500), 360);
+    /// assert_eq!(rules.bill(540), 720);
+    /// ```
+    pub fn bill(&self, seconds: u64) -> u64 {
+        let seconds = seconds.max(self.minimum);
+        if self.increment == 0 {
+            return seconds;
+        }
+
+        let blocks = match self.rounding {
+            Rounding::Up => seconds.div_ceil(self.increment),
+            Rounding::Nearest => (seconds + self.increment / 2) / self.increment,
+        };
+        return blocks * self.increment;
+    }
+
+    /// Gets the client of a repo.
+    pub fn client(&self, repo: &str) -> Option<&str> {
+        return self.clients.get(repo).map(|v| v.as_str());
+    }
+
+    /// Gets the hourly rate of a repo, from its own rate, the rate of its client or the default.
+    pub fn rate(&self, repo: &str) -> f64 {
+        let client_rate = self.client(repo).and_then(|v| self.rates.get(v));
+        return *self.rates.get(repo).or(client_rate).unwrap_or(&self.default_rate);
+    }
+}
+
+/// A billed session of a [`Timesheet`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TimesheetEntry {
+    /// The day the session started on (such as `2024-05-18`.)
+    pub date: String,
+    /// The time the session started at (such as `09:30`.)
+    pub start: String,
+    /// The time the session ended at.
+    pub end: String,
+    /// The label of the repo of the session.
+    pub repo: String,
+    /// The client of the repo.
+    pub client: Option<String>,
+    /// The identity of the author of the session.
+    pub author: String,
+    /// The titles of the commits of the session, oldest first.
+    pub description: String,
+    /// The seconds the session took.
+    pub seconds: u64,
+    /// The seconds billed for the session.
+    pub billed_seconds: u64,
+    /// The hourly rate of the session.
+    pub rate: f64,
+    /// The amount billed for the session.
+    pub amount: f64,
+}
+
+/// Sessions turned into billable time, the earliest start first.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Timesheet {
+    /// The billed sessions.
+    pub entries: Vec<TimesheetEntry>,
+    /// The seconds of every session.
+    pub seconds: u64,
+    /// The seconds billed for every session.
+    pub billed_seconds: u64,
+    /// The amount billed for every session.
+    pub amount: f64,
+}
+
+/// Formats seconds as hours with two decimals.
+fn hours(seconds: u64) -> String {
+    return format!("{:.2}", seconds as f64 / 3600.0);
+}
+
+impl Timesheet {
+    /// Bills every session with the rules, in the local time of its commits.
+    pub fn new(sessions: &[WorkSession], rules: &BillingRules) -> Self {
+        let mut timesheet = Self::default();
+
+        let mut sessions = sessions.iter().collect::<Vec<&WorkSession>>();
+        sessions.sort_by_key(|v| v.start);
+        for session in sessions {
+            let offset = session.commits.last().map_or(0, |v| v.offset as i64);
+            let local = |timestamp: i64| DateTime::from_timestamp(timestamp + offset, 0).unwrap_or_default();
+
+            let seconds = session.duration();
+            let billed_seconds = rules.bill(seconds);
+            let rate = rules.rate(&session.repo);
+            let amount = billed_seconds as f64 / 3600.0 * rate;
+
+            timesheet.seconds += seconds;
+            timesheet.billed_seconds += billed_seconds;
+            timesheet.amount += amount;
+            timesheet.entries.push(TimesheetEntry {
+                date: local(session.start).format("%Y-%m-%d").to_string(),
+                start: local(session.start).format("%H:%M").to_string(),
+                end: local(session.end).format("%H:%M").to_string(),
+                repo: session.repo.clone(),
+                client: rules.client(&session.repo).map(|v| v.to_string()),
+                author: session.author.clone(),
+                description: session.commits
+                    .iter()
+                    .rev()
+                    .map(|v| v.title.as_str())
+                    .collect::<Vec<&str>>()
+                    .join("; "),
+                seconds,
+                billed_seconds,
+                rate,
+                amount,
+            });
+        }
+
+        return timesheet;
+    }
+
+    /// Writes the timesheet as CSV with a row for every session and a last row with the totals.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("date,start,end,client,repo,author,description,hours,billed_hours,rate,amount\n");
+        for entry in &self.entries {
+            let fields = [
+                entry.date.clone(),
+                entry.start.clone(),
+                entry.end.clone(),
+                entry.client.clone().unwrap_or_default(),
+                entry.repo.clone(),
+                entry.author.clone(),
+                entry.description.clone(),
+                hours(entry.seconds),
+                hours(entry.billed_seconds),
+                format!("{:.2}", entry.rate),
+                format!("{:.2}", entry.amount),
+            ];
+            out += &fields.iter().map(|v| csv_field(v)).collect::<Vec<String>>().join(",");
+            out += "\n";
+        }
+        out += &format!(
+            "Total,,,,,,,{},{},,{:.2}\n",
+            hours(self.seconds), hours(self.billed_seconds), self.amount,
+        );
+        return out;
+    }
+
+    /// Writes the timesheet as a Markdown table with a row for every session and a last row with
+    /// the totals.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("| Date | Time | Client | Repo | Description | Hours | Billed | Rate | Amount |\n");
+        out += "|---|---|---|---|---|---:|---:|---:|---:|\n";
+        for entry in &self.entries {
+            out += &format!(
+                "| {} | {}-{} | {} | {} | {} | {} | {} | {:.2} | {:.2} |\n",
+                entry.date,
+                entry.start,
+                entry.end,
+                entry.client.as_deref().unwrap_or(""),
+                entry.repo,
+                entry.description.replace('|', "\\|"),
+                hours(entry.seconds),
+                hours(entry.billed_seconds),
+                entry.rate,
+                entry.amount,
+            );
+        }
+        out += &format!(
+            "| **Total** | | | | | {} | **{}** | | **{:.2}** |\n",
+            hours(self.seconds), hours(self.billed_seconds), self.amount,
+        );
+        return out;
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    heatmap::WEEKDAYS,
+    session::WorkSession,
+    summary::Period,
+};
+
+/// The amount of days shown by a [`ContributionCalendar`] before the week of its last day.
+const YEAR_DAYS: u64 = 364;
+
+/// The amount of idle gaps kept by a [`ContributionCalendar`].
+const GAP_COUNT: usize = 5;
+
+/// The characters used for the intensity levels of a rendered calendar, from no commits to the
+/// most commits.
+const BLOCKS: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+/// The colors used for the intensity levels of the SVG calendar.
+const COLORS: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+
+/// The size of a day in the SVG calendar with the space around it.
+const CELL: usize = 13;
+
+/// A day of a [`ContributionCalendar`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ContributionDay {
+    /// The date of the day (such as `2024-05-18`.)
+    pub date: String,
+    /// The amount of commits made on the day.
+    pub commits: usize,
+    /// The intensity of the day from 0 (no commits) to 4 (the most commits.)
+    pub level: usize,
+}
+
+/// A stretch of days, either all with commits (a streak) or all without (an idle gap.)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DayRange {
+    /// The first day of the range.
+    pub start: String,
+    /// The last day of the range.
+    pub end: String,
+    /// The amount of days in the range.
+    pub days: u64,
+}
+
+impl DayRange {
+    /// Creates a range from its first and last day.
+    pub fn new(start: NaiveDate, end: NaiveDate) -> Self {
+        return Self {
+            start: start.to_string(),
+            end: end.to_string(),
+            days: (end - start).num_days() as u64 + 1,
+        };
+    }
+}
+
+/// A year of days with commits like the contribution graph of a profile, along with the streaks
+/// and idle gaps of every commit.
+/// ```
+/// # use git_stats::calendar::ContributionCalendar;
+/// # use chrono::NaiveDate;
+/// let date = |day: u32| NaiveDate::from_ymd_opt(2024, 5, day).unwrap();
+/// let calendar = ContributionCalendar::new(
+///     [date(1), date(2), date(2), date(3), date(10), date(11)],
+///     date(12),
+/// );
+/// assert_eq!(calendar.longest_streak.unwrap().days, 3);
+/// assert_eq!(calendar.current_streak.unwrap().days, 2);
+/// assert_eq!(calendar.longest_gaps[0].days, 6);
+/// assert_eq!(calendar.days.last().unwrap().date, "2024-05-12");
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ContributionCalendar {
+    /// Every day from the start of the week a year before the last day, oldest first.
+    pub days: Vec<ContributionDay>,
+    /// The streak that ends on the last day or the day before it.
+    pub current_streak: Option<DayRange>,
+    /// The longest streak of days with commits.
+    pub longest_streak: Option<DayRange>,
+    /// The longest stretches of days without commits between two commits, longest first.
+    pub longest_gaps: Vec<DayRange>,
+}
+
+impl ContributionCalendar {
+    /// Creates a calendar from the dates commits were made on, the year of days ending on `end`.
+    pub fn new(dates: impl IntoIterator<Item = NaiveDate>, end: NaiveDate) -> Self {
+        let mut counts: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+        for date in dates {
+            *counts.entry(date).or_default() += 1;
+        }
+
+        let start = Period::Week.start(end - Days::new(YEAR_DAYS));
+        let most = counts.range(start..=end).map(|(_, v)| *v).max().unwrap_or(0);
+        let days = start
+            .iter_days()
+            .take_while(|v| *v <= end)
+            .map(|date| {
+                let commits = counts.get(&date).copied().unwrap_or(0);
+                return ContributionDay {
+                    date: date.to_string(),
+                    commits,
+                    level: match commits {
+                        0 => 0,
+                        v => (v * (BLOCKS.len() - 1)).div_ceil(most),
+                    },
+                };
+            })
+            .collect();
+
+        let mut streaks: Vec<(NaiveDate, NaiveDate)> = vec![];
+        let mut gaps: Vec<DayRange> = vec![];
+        for date in counts.into_keys() {
+            match streaks.last_mut() {
+                Some((_, last)) if last.succ_opt() == Some(date) => *last = date,
+                Some((_, last)) => {
+                    gaps.push(DayRange::new(*last + Days::new(1), date - Days::new(1)));
+                    streaks.push((date, date));
+                },
+                None => streaks.push((date, date)),
+            }
+        }
+
+        gaps.sort_by_key(|v| std::cmp::Reverse(v.days));
+        gaps.truncate(GAP_COUNT);
+
+        return Self {
+            days,
+            current_streak: streaks
+                .iter()
+                .find(|(_, last)| *last == end || last.succ_opt() == Some(end))
+                .map(|(first, last)| DayRange::new(*first, *last)),
+            longest_streak: streaks
+                .iter()
+                .max_by_key(|(first, last)| (*last - *first, *last))
+                .map(|(first, last)| DayRange::new(*first, *last)),
+            longest_gaps: gaps,
+        };
+    }
+
+    /// Creates a calendar from the commits of every session, each counting toward the day it was
+    /// made on in its own timezone.
+    pub fn from_sessions(sessions: &[WorkSession], end: NaiveDate) -> Self {
+        let dates = sessions
+            .iter()
+            .flat_map(|v| &v.commits)
+            .filter_map(|v| DateTime::from_timestamp(v.timestamp + v.offset as i64, 0))
+            .map(|v| v.date_naive());
+        return Self::new(dates, end);
+    }
+
+    /// Gets the days of the calendar split into weeks starting on monday.
+    fn weeks(&self) -> Vec<&[ContributionDay]> {
+        return self.days.chunks(WEEKDAYS.len()).collect();
+    }
+
+    /// Gets the name of the month of every week if it starts in a different month than the week
+    /// before it.
+    fn month_labels(&self) -> Vec<Option<String>> {
+        let first = match self.days.first() {
+            Some(v) => NaiveDate::parse_from_str(&v.date, "%Y-%m-%d").unwrap(),
+            None => return vec![],
+        };
+
+        return (0..self.weeks().len() as u64)
+            .map(|column| {
+                let date = first + Days::new(column * WEEKDAYS.len() as u64);
+                let previous = date - Days::new(WEEKDAYS.len() as u64);
+                return match column == 0 || date.month() != previous.month() {
+                    true => Some(date.format("%b").to_string()),
+                    false => None,
+                };
+            })
+            .collect();
+    }
+
+    /// Renders the calendar as block characters for a terminal, with a row for every day of the
+    /// week and a column for every week.
+    pub fn render(&self) -> String {
+        let weeks = self.weeks();
+
+        let mut out = String::from("    ");
+        let mut label_end = 0;
+        for (column, label) in self.month_labels().into_iter().enumerate() {
+            if let Some(label) = label.filter(|_| column >= label_end) {
+                out += &" ".repeat(column - label_end);
+                out += &label;
+                label_end = column + label.len();
+            }
+        }
+        out += "\n";
+
+        for (weekday, name) in WEEKDAYS.iter().enumerate() {
+            out += &format!("{name} ");
+            for week in &weeks {
+                out.push(week.get(weekday).map_or(' ', |v| BLOCKS[v.level]));
+            }
+            out += "\n";
+        }
+
+        let total = self.days.iter().map(|v| v.commits).sum::<usize>();
+        out += &format!("{total} commits in the last year\n");
+        if let Some(v) = &self.current_streak {
+            out += &format!("Current streak: {} days ({} to {})\n", v.days, v.start, v.end);
+        }
+        if let Some(v) = &self.longest_streak {
+            out += &format!("Longest streak: {} days ({} to {})\n", v.days, v.start, v.end);
+        }
+        if let Some(v) = self.longest_gaps.first() {
+            out += &format!("Longest idle gap: {} days ({} to {})\n", v.days, v.start, v.end);
+        }
+        return out;
+    }
+
+    /// Renders the calendar as an SVG image, with a square for every day colored by its level.
+    pub fn to_svg(&self) -> String {
+        let weeks = self.weeks();
+        let (left, top) = (30, 20);
+        let width = left + weeks.len() * CELL;
+        let height = top + WEEKDAYS.len() * CELL;
+
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" font-family=\"sans-serif\" font-size=\"9\" fill=\"#767676\">\n"
+        );
+
+        for (column, label) in self.month_labels().into_iter().enumerate() {
+            if let Some(label) = label {
+                out += &format!("<text x=\"{}\" y=\"{}\">{label}</text>\n", left + column * CELL, top - 8);
+            }
+        }
+
+        for (weekday, name) in WEEKDAYS.iter().enumerate().filter(|(v, _)| v % 2 == 0) {
+            out += &format!("<text x=\"0\" y=\"{}\">{name}</text>\n", top + weekday * CELL + 9);
+        }
+
+        for (column, week) in weeks.iter().enumerate() {
+            for (weekday, day) in week.iter().enumerate() {
+                out += &format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"11\" height=\"11\" rx=\"2\" fill=\"{}\"><title>{}: {} commits</title></rect>\n",
+                    left + column * CELL, top + weekday * CELL, COLORS[day.level], day.date, day.commits,
+                );
+            }
+        }
+
+        out += "</svg>\n";
+        return out;
+    }
+}
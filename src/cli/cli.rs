@@ -1,56 +1,830 @@
 use clap::{
+    error::ErrorKind,
+    ArgGroup,
+    CommandFactory,
     Parser,
+    Subcommand,
+    ValueEnum,
     command
 };
 
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate, Weekday};
 use log::LevelFilter;
-use git_stats::macros::clap_enum_variants;
+use git_stats::{
+    billing::{BillingRules, Rounding},
+    estimate::{
+        AverageEstimator, ClampedEstimator, Estimator, FixedEstimator, GapCappedEstimator,
+        MedianEstimator, PerAuthorEstimator,
+    },
+    macros::clap_enum_variants,
+    report::StatsBuilder,
+    session::DEFAULT_WINDOW,
+    stats::DEFAULT_TRIM,
+    summary::Period,
+    timestamps::TimestampSource,
+    Repo,
+};
+
+use super::{
+    config::{Config, Workspace},
+    scan::{self, DEFAULT_SKIP},
+};
+
+/// Parses a duration such as `5h`, `90m`, `1h30m` or `3600` (seconds) into seconds.
+/// Durations must be longer than zero.
+pub fn parse_duration(in_str: &str) -> Result<u64> {
+    let in_str = in_str.trim();
+    if in_str.is_empty() {
+        return Err(anyhow!("Duration can't be empty"));
+    }
+
+    let too_long = || anyhow!("Duration '{in_str}' is too long");
+    let not_zero = |v: u64| match v {
+        0 => Err(anyhow!("Duration '{in_str}' must be longer than zero")),
+        _ => Ok(v),
+    };
+    if let Ok(v) = in_str.parse::<u64>() {
+        // Durations are added to unix timestamps
+        return i64::try_from(v).map_err(|_| too_long()).and_then(|_| not_zero(v));
+    }
+
+    let mut total: u64 = 0;
+    let mut number = String::new();
+    for c in in_str.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let unit = match c {
+            'd' => 3600 * 24,
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(anyhow!("Unknown duration unit '{c}' in '{in_str}'")),
+        };
+        let value: u64 = number.parse().map_err(|_| anyhow!("Can't parse duration '{in_str}'"))?;
+        total = value
+            .checked_mul(unit)
+            .and_then(|v| total.checked_add(v))
+            .filter(|v| i64::try_from(*v).is_ok())
+            .ok_or_else(too_long)?;
+        number.clear();
+    }
+
+    if !number.is_empty() {
+        return Err(anyhow!("Duration '{in_str}' is missing a unit at the end"));
+    }
+    return not_zero(total);
+}
+
+/// Parses an author window override such as `someone@email.tld=8h`.
+pub fn parse_author_window(in_str: &str) -> Result<(String, u64)> {
+    let (author, window) = in_str
+        .rsplit_once('=')
+        .ok_or(anyhow!("Author window '{in_str}' must look like 'author=duration'"))?;
+    return Ok((author.to_string(), parse_duration(window)?));
+}
+
+/// Parses an hourly rate such as `100` (the default rate) or `acme=120` (the rate of a repo or
+/// client.)
+pub fn parse_rate(in_str: &str) -> Result<(Option<String>, f64)> {
+    let (name, rate) = match in_str.rsplit_once('=') {
+        Some((name, rate)) => (Some(name.to_string()), rate),
+        None => (None, in_str),
+    };
+    let rate = rate.trim().parse::<f64>().map_err(|_| anyhow!("Can't parse rate '{in_str}'"))?;
+    return Ok((name, rate));
+}
+
+/// Parses the client of a repo such as `api=acme`.
+pub fn parse_client(in_str: &str) -> Result<(String, String)> {
+    let (repo, client) = in_str
+        .split_once('=')
+        .ok_or(anyhow!("Client '{in_str}' must look like 'repo=client'"))?;
+    return Ok((repo.to_string(), client.to_string()));
+}
+
+/// Gets the unix timestamp of the start of a day in local time.
+fn local_midnight(date: NaiveDate) -> i64 {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    return match midnight.and_local_timezone(Local).earliest() {
+        Some(v) => v.timestamp(),
+        // Midnight doesn't exist on some daylight saving days
+        None => midnight.and_utc().timestamp(),
+    };
+}
+
+/// Gets the first and last second of the days from `start` up to (not including) `end`.
+fn local_days(start: NaiveDate, end: NaiveDate) -> (i64, i64) {
+    return (local_midnight(start), local_midnight(end) - 1);
+}
+
+/// Parses a date such as `2024-05-18`, `2024-W20`, `2024-05-18T12:00:00Z`, `yesterday`,
+/// `last week` or `3 days ago` into the unix timestamps of its first and last second, days being
+/// in local time.
+pub fn parse_date_range(in_str: &str, now: DateTime<Local>) -> Result<(i64, i64)> {
+    let in_str = in_str.trim().to_lowercase();
+    let today = now.date_naive();
+    let week_start = today - Days::new(today.weekday().num_days_from_monday() as u64);
+    let month_start = today.with_day(1).unwrap();
+
+    match in_str.as_str() {
+        "now" => return Ok((now.timestamp(), now.timestamp())),
+        "today" => return Ok(local_days(today, today + Days::new(1))),
+        "yesterday" => return Ok(local_days(today - Days::new(1), today)),
+        "this week" => return Ok(local_days(week_start, week_start + Days::new(7))),
+        "last week" => return Ok(local_days(week_start - Days::new(7), week_start)),
+        "this month" => return Ok(local_days(month_start, month_start + Months::new(1))),
+        "last month" => return Ok(local_days(month_start - Months::new(1), month_start)),
+        _ => (),
+    }
+
+    if let Some(amount) = in_str.strip_suffix(" ago") {
+        let (number, unit) = amount
+            .split_once(' ')
+            .ok_or(anyhow!("Relative date '{in_str}' must look like '3 days ago'"))?;
+        let number: u32 = number.trim().parse().map_err(|_| anyhow!("Can't parse amount in '{in_str}'"))?;
+
+        let seconds = |unit: i64| chrono::Duration::try_seconds(number as i64 * unit)
+            .and_then(|v| now.checked_sub_signed(v));
+        let time = match unit.trim().trim_end_matches('s') {
+            "second" => seconds(1),
+            "minute" => seconds(60),
+            "hour" => seconds(3600),
+            "day" => now.checked_sub_days(Days::new(number as u64)),
+            "week" => now.checked_sub_days(Days::new(number as u64 * 7)),
+            "month" => now.checked_sub_months(Months::new(number)),
+            "year" => number.checked_mul(12).and_then(|v| now.checked_sub_months(Months::new(v))),
+            unit => return Err(anyhow!("Unknown unit '{unit}' in '{in_str}'")),
+        };
+        let time = time.ok_or(anyhow!("Date '{in_str}' is too far in the past"))?;
+        return Ok((time.timestamp(), time.timestamp()));
+    }
+
+    if let Some((year, week)) = in_str.split_once("-w") {
+        let year: i32 = year.parse().map_err(|_| anyhow!("Can't parse year of ISO week '{in_str}'"))?;
+        let week: u32 = week.parse().map_err(|_| anyhow!("Can't parse week of ISO week '{in_str}'"))?;
+        let start = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
+            .ok_or(anyhow!("ISO week '{in_str}' doesn't exist"))?;
+        return Ok(local_days(start, start + Days::new(7)));
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(&in_str, "%Y-%m-%d") {
+        return Ok(local_days(date, date + Days::new(1)));
+    }
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(&in_str) {
+        return Ok((time.timestamp(), time.timestamp()));
+    }
+
+    return Err(anyhow!("Can't parse date '{in_str}'"));
+}
+
+/// Parses a date into the unix timestamp it starts at (see [`parse_date_range`].)
+pub fn parse_since(in_str: &str) -> Result<i64> {
+    return Ok(parse_date_range(in_str, Local::now())?.0);
+}
 
+/// Parses a date into the unix timestamp it ends at (see [`parse_date_range`].)
+pub fn parse_until(in_str: &str) -> Result<i64> {
+    return Ok(parse_date_range(in_str, Local::now())?.1);
+}
+
+/// The strategies that can be used to project the start of a work session.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EstimatorKind {
+    /// The average lines per second of every commit.
+    Average,
+    /// The median lines per second of every commit.
+    Median,
+    /// The average lines per second of the author of the commit.
+    PerAuthor,
+    /// A fixed amount of time (set with `--estimate-duration`.)
+    Fixed,
+    /// The usual gap between the commits of the author capped to `--estimate-duration`.
+    GapCapped,
+}
+
+/// The timestamp of a commit used as the time it happened.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeSource {
+    /// The committer time.
+    Committer,
+    /// The author time.
+    Author,
+    /// The committer time unless it is out of order or in the future and the author time isn't.
+    Auto,
+}
+
+/// The periods the commits can be totaled by.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryPeriod {
+    /// Every day.
+    Day,
+    /// Every ISO week.
+    Week,
+    /// Every month.
+    Month,
+}
+
+/// The ways the contribution calendar can be output.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarFormat {
+    /// Block characters for a terminal.
+    Terminal,
+    /// An SVG image.
+    Svg,
+}
+
+/// The ways a timesheet can be output.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimesheetFormat {
+    /// Comma separated values.
+    Csv,
+    /// A Markdown table.
+    Markdown,
+}
+
+/// How sessions are rounded in timesheets.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Always up to the next increment.
+    Up,
+    /// To the nearest increment.
+    Nearest,
+}
+
+/// The formats commits and sessions can be output in.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The nested JSON of the sessions and their commits (or the totals of every author.)
+    Json,
+    /// Comma separated values, a row for every commit or session.
+    Csv,
+    /// JSON Lines, an object for every commit or session.
+    Ndjson,
+}
+
+/// What every row of the CSV and JSON Lines formats is.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowKind {
+    /// A row for every commit.
+    Commit,
+    /// A row for every session.
+    Session,
+}
+
+/// The commands other than walking the repos passed with `--directory` or `--workspace`.
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Finds every repo under a directory and reports on all of them together.
+    Scan {
+        /// The directory to search for repos.
+        root: String,
+
+        /// How many directories deep repos are searched for.
+        #[clap(long, default_value="3")]
+        max_depth: usize,
+
+        /// Names of directories that are never searched along with `node_modules` and `target`
+        /// (can be used multiple times.)
+        #[clap(long)]
+        skip: Vec<String>,
+    },
+}
+
+/// The arguments that output something other than the commits, which can't be written as flat
+/// rows. Only one of them can be used at once (in the `output` group), other than `--per-author`
+/// which also changes how sessions are made.
+const OUTPUT_MODES: [&str; 8] = ["summary", "heatmap", "calendar", "by_type", "tickets", "timesheet", "dropped_samples", "per_author"];
 
 /// A utility for parsing through git repos
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(version, about, long_about = None)]
+#[clap(group = ArgGroup::new("output").multiple(false))]
 pub struct CliArgs {
-    /// The path to the repo
+    /// Runs a command instead of walking `--directory`.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// The path to the repo. Can be used multiple times to merge the sessions of several repos.
     #[clap(short, long, default_value=".")]
-    pub directory: String,
+    pub directory: Vec<String>,
+
+    /// The path to a JSON workspace file listing the repos to use instead of `--directory`.
+    #[clap(long, default_value=None)]
+    pub workspace: Option<String>,
 
-    /// The branch being targeted
-    #[clap(short, long, default_value="main")]
-    pub branch: String,
+    /// The branches being targeted, as names, full refs or globs such as `refs/heads/*`.
+    /// Can be used multiple times. Defaults to `main`, or the checked out branch of every
+    /// repository found by `scan`.
+    #[clap(global=true, short, long)]
+    pub branch: Vec<String>,
 
-    /// Enable parsing by email
-    #[clap(short, long, default_value=None)]
+    /// Only count the commits of the author with this email
+    #[clap(global=true, short, long, default_value=None)]
     pub email: Option<String>,
 
-    /// Enable parsing by committer name
-    #[clap(short, long, default_value=None)]
+    /// Only count the commits of the author with this name
+    #[clap(global=true, short, long, default_value=None)]
     pub committer: Option<String>,
 
+    /// The amount of lines a KiB of changes to a binary file counts as when projecting time.
+    #[clap(global=true, long, default_value="1.0")]
+    pub binary_weight: f32,
+
+    /// The amount of lines a KiB of changes to a Git LFS object counts as when projecting time.
+    /// Uses the binary weight if not set.
+    #[clap(global=true, long, default_value=None)]
+    pub lfs_weight: Option<f32>,
+
+    /// Only count files matching this pathspec (can be used multiple times.)
+    #[clap(global=true, long)]
+    pub include: Vec<String>,
+
+    /// Don't count files matching this pathspec (can be used multiple times.)
+    #[clap(global=true, long)]
+    pub exclude: Vec<String>,
+
+    /// Only count commits made on or after this date (such as `2024-05-18`, `2024-W20`,
+    /// `last week` or `3 days ago`.)
+    #[clap(global=true, long, default_value=None, value_parser=parse_since)]
+    pub since: Option<i64>,
+
+    /// Only count commits made on or before this date (same formats as `--since`.)
+    #[clap(global=true, long, default_value=None, value_parser=parse_until)]
+    pub until: Option<i64>,
+
+    /// Stops after counting this many commits.
+    #[clap(global=true, short='n', long, default_value=None)]
+    pub max_count: Option<usize>,
+
+    /// Creates separate sessions for every author and outputs the totals of each author instead
+    /// of the commits.
+    #[clap(global=true, long, action)]
+    pub per_author: bool,
+
+    /// Outputs the totals of every day, week or month (in local time) instead of the commits.
+    #[clap(global=true, group="output", long, value_enum, default_value=None)]
+    pub summary: Option<SummaryPeriod>,
+
+    /// The format of the output. CSV and JSON Lines have flat rows with stable columns.
+    /// Can't be used with the other outputs or `--per-author`.
+    #[clap(global=true, short='f', long, value_enum, default_value_t=OutputFormat::Json, conflicts_with_all=OUTPUT_MODES)]
+    pub format: OutputFormat,
+
+    /// What every row of the CSV and JSON Lines formats is, commits by default.
+    /// Can't be used with the nested JSON.
+    #[clap(global=true, long, value_enum, default_value=None, requires="format")]
+    pub rows: Option<RowKind>,
+
+    /// Outputs a heatmap of the time worked in every hour of the week (in the local time of each
+    /// commit) instead of the commits.
+    #[clap(global=true, group="output", long, action)]
+    pub heatmap: bool,
+
+    /// Outputs a year of days with commits along with streaks and idle gaps instead of the
+    /// commits, the year ending on `--until` or today.
+    #[clap(global=true, group="output", long, value_enum, default_value=None)]
+    pub calendar: Option<CalendarFormat>,
+
+    /// Outputs the time spent on every Conventional Commit type and scope (such as `feat` or
+    /// `fix`) instead of the commits.
+    #[clap(global=true, group="output", long, action)]
+    pub by_type: bool,
+
+    /// Outputs the time spent on every ticket referenced by commit titles or branch names instead
+    /// of the commits.
+    #[clap(global=true, group="output", long, action)]
+    pub tickets: bool,
+
+    /// A regex that finds tickets in commit titles and branch names (such as `[A-Z]+-\d+`.)
+    /// Can be used multiple times, defaults to Jira keys and `#123` issues.
+    #[clap(global=true, long)]
+    pub ticket_pattern: Vec<String>,
+
+    /// Outputs a timesheet of the billed time of every session instead of the commits.
+    #[clap(global=true, group="output", long, value_enum, default_value=None)]
+    pub timesheet: Option<TimesheetFormat>,
+
+    /// The increment sessions are rounded to in timesheets (such as `6m`, `15m` or `30m`.)
+    #[clap(global=true, long, default_value="15m", value_parser=parse_duration)]
+    pub round: u64,
+
+    /// How sessions are rounded to the increment in timesheets.
+    #[clap(global=true, long, value_enum, default_value_t=RoundingMode::Up)]
+    pub rounding: RoundingMode,
+
+    /// The shortest time a session is billed for in timesheets.
+    #[clap(global=true, long, default_value=None, value_parser=parse_duration)]
+    pub minimum: Option<u64>,
+
+    /// The hourly rate in timesheets (such as `100`), or the rate of a repo or client (such as
+    /// `acme=120`.) Can be used multiple times.
+    #[clap(global=true, long, value_parser=parse_rate)]
+    pub rate: Vec<(Option<String>, f64)>,
+
+    /// Sets the client of a repo in timesheets (such as `api=acme`.) Can be used multiple times.
+    #[clap(global=true, long, value_parser=parse_client)]
+    pub client: Vec<(String, String)>,
+
+    /// The longest gap between two commits of the same work session (such as `5h` or `90m`.)
+    /// Defaults to 5h.
+    #[clap(global=true, short, long, default_value=None, value_parser=parse_duration)]
+    pub window: Option<u64>,
+
+    /// Sets the window for an author by email or name (such as `someone@email.tld=8h`.)
+    /// Can be used multiple times.
+    #[clap(global=true, long, value_parser=parse_author_window)]
+    pub author_window: Vec<(String, u64)>,
+
+    /// Which timestamp of a commit is used as the time it happened.
+    #[clap(global=true, long, value_enum, default_value_t=TimeSource::Committer)]
+    pub time: TimeSource,
+
+    /// The strategy used to project how long the first commit of a session took.
+    #[clap(global=true, long, value_enum, default_value_t=EstimatorKind::Average)]
+    pub estimator: EstimatorKind,
+
+    /// The fraction of rates left out of averages from each end as outliers.
+    #[clap(global=true, long, default_value_t=DEFAULT_TRIM)]
+    pub rate_trim: f32,
+
+    /// Outputs how many commits were left out of the rates used to project session starts (and
+    /// why) instead of the commits.
+    #[clap(global=true, group="output", long, action)]
+    pub dropped_samples: bool,
+
+    /// The duration used by the fixed and gap-capped estimators, and by any estimator without
+    /// commits to project from.
+    #[clap(global=true, long, default_value="30m", value_parser=parse_duration)]
+    pub estimate_duration: u64,
+
+    /// The shortest a projected session start can be.
+    #[clap(global=true, long, default_value=None, value_parser=parse_duration)]
+    pub estimate_min: Option<u64>,
+
+    /// The longest a projected session start can be.
+    #[clap(global=true, long, default_value=None, value_parser=parse_duration)]
+    pub estimate_max: Option<u64>,
+
+    /// The path to a JSON config file, values on the command line take precedence.
+    #[clap(global=true, long, default_value=None)]
+    pub config: Option<String>,
+
     /// The file to write the output to
-    #[clap(short, long, default_value=None)]
+    #[clap(global=true, short, long, default_value=None)]
     pub outfile: Option<String>,
 
     /// Flag as to if this should start a server.
-    #[clap(short='S', long, action)]
+    #[clap(global=true, short='S', long, action)]
     pub server: bool,
 
     /// The directory for the static server files.
-    #[clap(short='D', long, default_value="./static")]
+    #[clap(global=true, short='D', long, default_value="./static")]
     pub server_directory: String,
 
     /// The port to run the server on.
-    #[clap(short='P', long, default_value="8080")]
+    #[clap(global=true, short='P', long, default_value="8080")]
     pub server_port: u32,
 
     /// Sets the URI the server serves repo stats on.
-    #[clap(short='U', long, default_value="/api/data")]
+    #[clap(global=true, short='U', long, default_value="/api/data")]
     pub server_uri: String,
 
     /// Sets the verbosity of logs
-    #[arg(long,
+    #[arg(long, global=true,
           default_value_t=LevelFilter::Off,
           value_name="LevelFilter",
           value_parser=clap_enum_variants!(LevelFilter))]
     pub logs: LevelFilter,
 }
+
+impl CliArgs {
+    /// Exits with a usage error for the combinations of arguments that clap can't rule out itself.
+    pub fn validate(&self) {
+        if self.rows.is_some() && self.format == OutputFormat::Json {
+            Self::command()
+                .error(ErrorKind::ArgumentConflict, "'--rows' can only be used with '--format csv' or '--format ndjson'")
+                .exit();
+        }
+    }
+
+    /// Fills in the values that weren't set on the command line from a config file.
+    pub fn apply_config(&mut self, config: &Config) -> Result<()> {
+        if self.window.is_none() {
+            self.window = config.window
+                .as_deref()
+                .map(parse_duration)
+                .transpose()?;
+        }
+
+        // Command line windows are added last so they override the config.
+        let mut author_windows = config.author_windows
+            .iter()
+            .map(|(author, window)| Ok((author.to_owned(), parse_duration(window)?)))
+            .collect::<Result<Vec<(String, u64)>>>()?;
+        author_windows.append(&mut self.author_window);
+        self.author_window = author_windows;
+
+        if self.ticket_pattern.is_empty() {
+            self.ticket_pattern = config.ticket_patterns.clone();
+        }
+
+        // Command line rates and clients are added last so they override the config.
+        let mut rates = config.rates
+            .iter()
+            .map(|(name, rate)| (Some(name.to_owned()), *rate))
+            .collect::<Vec<(Option<String>, f64)>>();
+        rates.append(&mut self.rate);
+        self.rate = rates;
+
+        let mut clients = config.clients
+            .iter()
+            .map(|(repo, client)| (repo.to_owned(), client.to_owned()))
+            .collect::<Vec<(String, String)>>();
+        clients.append(&mut self.client);
+        self.client = clients;
+
+        return Ok(());
+    }
+
+    /// Gets the paths and labels of the repos, from the workspace file if one was passed or found
+    /// by the scan command.
+    pub fn repositories(&self) -> Result<Vec<(String, Option<String>)>> {
+        if let Some(Command::Scan { root, max_depth, skip }) = &self.command {
+            let skip = DEFAULT_SKIP
+                .iter()
+                .map(|v| v.to_string())
+                .chain(skip.iter().cloned())
+                .collect::<Vec<String>>();
+            return Ok(scan::find_repositories(Path::new(root), *max_depth, &skip)
+                .into_iter()
+                .map(|v| (v.to_string_lossy().to_string(), None))
+                .collect());
+        }
+
+        return match &self.workspace {
+            Some(path) => Ok(Workspace::from_path(path)?
+                .repositories
+                .into_iter()
+                .map(|v| (v.path, v.name))
+                .collect()),
+            None => Ok(self.directory.iter().map(|v| (v.to_owned(), None)).collect()),
+        };
+    }
+
+    /// Creates a stats builder for every repo from the arguments. Scanned repos only walk the
+    /// branches they have and are skipped if they have none of them.
+    pub fn stats_builders(&self) -> Result<Vec<StatsBuilder>> {
+        let repositories = self.repositories()?;
+        if repositories.is_empty() {
+            return Err(anyhow!("No repositories to walk"));
+        }
+
+        let mut builders = vec![];
+        for (path, name) in repositories {
+            let builder = self.stats_builder(&path).label(name);
+            if !matches!(self.command, Some(Command::Scan { .. })) {
+                builders.push(builder);
+                continue;
+            }
+
+            let branches = self.scan_branches(Path::new(&path));
+            if branches.is_empty() {
+                // Logs are off by default and a skipped repo would otherwise go unnoticed
+                eprintln!("Skipping repo without any of the branches: '{path}'");
+                continue;
+            }
+            builders.push(builder.revisions(branches));
+        }
+        return Ok(builders);
+    }
+
+    /// Gets the branches that are walked when none are set.
+    fn branches(&self) -> Vec<String> {
+        return match self.branch.is_empty() {
+            true => vec!["main".to_string()],
+            false => self.branch.clone(),
+        };
+    }
+
+    /// Gets the branches a scanned repo walks, the branches set that match any of its refs or the
+    /// checked out branch if none are set.
+    fn scan_branches(&self, path: &Path) -> Vec<String> {
+        let repo = match Repo::from_pathbuf(&path.to_path_buf()) {
+            Ok(v) => v,
+            Err(_) => return vec![],
+        };
+        if self.branch.is_empty() {
+            return repo.head_branch().into_iter().collect();
+        }
+        return self.branch
+            .iter()
+            .filter(|v| repo.resolve_refs(v).is_ok_and(|v| !v.is_empty()))
+            .cloned()
+            .collect();
+    }
+
+    /// Creates a stats builder for a repo from the arguments.
+    pub fn stats_builder(&self, directory: &str) -> StatsBuilder {
+        let mut builder = StatsBuilder::new(directory)
+            .revisions(self.branches())
+            .include(self.include.clone())
+            .exclude(self.exclude.clone())
+            .email(self.email.clone())
+            .committer(self.committer.clone())
+            .since(self.since)
+            .until(self.until)
+            .max_count(self.max_count)
+            .per_author(self.per_author)
+            .window(self.window.unwrap_or(DEFAULT_WINDOW))
+            .estimator(self.estimator())
+            .fallback_estimator(Some(self.fallback_estimator()))
+            .timestamp_source(self.timestamp_source())
+            .binary_weight(self.binary_weight)
+            .lfs_weight(self.lfs_weight)
+            .ticket_patterns(self.ticket_pattern.clone());
+        for (author, window) in &self.author_window {
+            builder = builder.author_window(author, *window);
+        }
+        return builder;
+    }
+
+    /// Gets the timestamp source chosen by the arguments.
+    pub fn timestamp_source(&self) -> TimestampSource {
+        return match self.time {
+            TimeSource::Committer => TimestampSource::Committer,
+            TimeSource::Author => TimestampSource::Author,
+            TimeSource::Auto => TimestampSource::Auto,
+        };
+    }
+
+    /// Gets the last day of the contribution calendar, the day of `--until` or today.
+    pub fn calendar_end(&self) -> NaiveDate {
+        let end = self.until
+            .and_then(|v| DateTime::from_timestamp(v, 0))
+            .map(|v| v.with_timezone(&Local))
+            .unwrap_or(Local::now());
+        return end.date_naive();
+    }
+
+    /// Gets the rules timesheets are billed with from the arguments.
+    pub fn billing_rules(&self) -> BillingRules {
+        let mut rules = BillingRules {
+            increment: self.round,
+            rounding: match self.rounding {
+                RoundingMode::Up => Rounding::Up,
+                RoundingMode::Nearest => Rounding::Nearest,
+            },
+            minimum: self.minimum.unwrap_or(0),
+            clients: self.client.iter().cloned().collect(),
+            ..Default::default()
+        };
+        for (name, rate) in &self.rate {
+            match name {
+                Some(name) => {
+                    rules.rates.insert(name.to_owned(), *rate);
+                },
+                None => rules.default_rate = *rate,
+            }
+        }
+        return rules;
+    }
+
+    /// Gets the period of the summary chosen by the arguments.
+    pub fn summary_period(&self) -> Option<Period> {
+        return self.summary.map(|v| match v {
+            SummaryPeriod::Day => Period::Day,
+            SummaryPeriod::Week => Period::Week,
+            SummaryPeriod::Month => Period::Month,
+        });
+    }
+
+    /// Creates the estimator chosen by the arguments.
+    pub fn estimator(&self) -> Box<dyn Estimator> {
+        let estimator: Box<dyn Estimator> = match self.estimator {
+            EstimatorKind::Average => Box::new(AverageEstimator::new(self.rate_trim)),
+            EstimatorKind::Median => Box::new(MedianEstimator::default()),
+            EstimatorKind::PerAuthor => Box::new(PerAuthorEstimator::new(self.rate_trim)),
+            EstimatorKind::Fixed => Box::new(FixedEstimator(self.estimate_duration)),
+            EstimatorKind::GapCapped => Box::new(GapCappedEstimator::new(self.estimate_duration)),
+        };
+        return self.clamped(estimator);
+    }
+
+    /// Creates the estimator used when the chosen one has nothing to project from, which gives
+    /// every session `--estimate-duration`.
+    pub fn fallback_estimator(&self) -> Box<dyn Estimator> {
+        return self.clamped(Box::new(FixedEstimator(self.estimate_duration)));
+    }
+
+    /// Keeps the estimates of `estimator` within `--estimate-min` and `--estimate-max`.
+    fn clamped(&self, estimator: Box<dyn Estimator>) -> Box<dyn Estimator> {
+        if self.estimate_min.is_none() && self.estimate_max.is_none() {
+            return estimator;
+        }
+        return Box::new(ClampedEstimator::new(estimator, self.estimate_min, self.estimate_max));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    /// Wednesday 2024-05-15 at noon.
+    fn now() -> DateTime<Local> {
+        return Local.with_ymd_and_hms(2024, 5, 15, 12, 0, 0).unwrap();
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("3600").unwrap(), 3600);
+        assert_eq!(parse_duration("90m").unwrap(), 5400);
+        assert_eq!(parse_duration(" 1h30m ").unwrap(), 5400);
+        assert_eq!(parse_duration("1d2h3m4s").unwrap(), 93784);
+    }
+
+    #[test]
+    fn malformed_durations() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("  ").is_err());
+        assert!(parse_duration("0").is_err());
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("0h0m").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("5x").is_err());
+        assert!(parse_duration("1h30").is_err());
+        assert!(parse_duration("-5m").is_err());
+    }
+
+    #[test]
+    fn duration_overflow() {
+        assert!(parse_duration("999999999999999d").is_err());
+        assert!(parse_duration("18446744073709551615").is_err());
+        assert!(parse_duration("99999999999999999999s").is_err());
+        assert!(parse_duration("9223372036854775807s1s").is_err());
+    }
+
+    #[test]
+    fn dates() {
+        let now = now();
+        assert_eq!(parse_date_range("now", now).unwrap(), (now.timestamp(), now.timestamp()));
+        assert_eq!(parse_date_range("today", now).unwrap(), local_days(date(2024, 5, 15), date(2024, 5, 16)));
+        assert_eq!(parse_date_range("Yesterday", now).unwrap(), local_days(date(2024, 5, 14), date(2024, 5, 15)));
+        assert_eq!(parse_date_range("this week", now).unwrap(), local_days(date(2024, 5, 13), date(2024, 5, 20)));
+        assert_eq!(parse_date_range("last week", now).unwrap(), local_days(date(2024, 5, 6), date(2024, 5, 13)));
+        assert_eq!(parse_date_range("last month", now).unwrap(), local_days(date(2024, 4, 1), date(2024, 5, 1)));
+        assert_eq!(parse_date_range("2024-05-18", now).unwrap(), local_days(date(2024, 5, 18), date(2024, 5, 19)));
+        assert_eq!(parse_date_range("2024-W20", now).unwrap(), local_days(date(2024, 5, 13), date(2024, 5, 20)));
+
+        let time = DateTime::parse_from_rfc3339("2024-05-18T12:00:00Z").unwrap().timestamp();
+        assert_eq!(parse_date_range("2024-05-18T12:00:00Z", now).unwrap(), (time, time));
+    }
+
+    #[test]
+    fn relative_dates() {
+        let now = now();
+        let ago = |v: &str| parse_date_range(v, now).unwrap().0;
+        assert_eq!(ago("90 seconds ago"), now.timestamp() - 90);
+        assert_eq!(ago("2 hours ago"), now.timestamp() - 7200);
+        assert_eq!(ago("3 days ago"), (now - Days::new(3)).timestamp());
+        assert_eq!(ago("1 week ago"), (now - Days::new(7)).timestamp());
+        assert_eq!(ago("1 month ago"), (now - Months::new(1)).timestamp());
+        assert_eq!(ago("2 years ago"), (now - Months::new(24)).timestamp());
+    }
+
+    #[test]
+    fn malformed_dates() {
+        let now = now();
+        assert!(parse_date_range("", now).is_err());
+        assert!(parse_date_range("someday", now).is_err());
+        assert!(parse_date_range("days ago", now).is_err());
+        assert!(parse_date_range("many days ago", now).is_err());
+        assert!(parse_date_range("3 fortnights ago", now).is_err());
+        assert!(parse_date_range("2024-13-01", now).is_err());
+        assert!(parse_date_range("2024-W54", now).is_err());
+        assert!(parse_date_range("x-W20", now).is_err());
+    }
+
+    #[test]
+    fn date_overflow() {
+        let now = now();
+        assert!(parse_date_range("100000000 days ago", now).is_err());
+        assert!(parse_date_range("1000000000 weeks ago", now).is_err());
+        assert!(parse_date_range("1000000000 years ago", now).is_err());
+        assert!(parse_date_range("4000000000 seconds ago", now).is_ok());
+        assert!(parse_date_range("4294967295 hours ago", now).is_err());
+        assert!(parse_date_range("4294967296 days ago", now).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// Values that can be set in the JSON config file passed with `--config`.
+/// Values set on the command line take precedence over the ones set here.
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct Config {
+    /// The longest gap between commits in the same work session (such as `"5h"`.)
+    pub window: Option<String>,
+    /// Windows for specific authors, by email or name.
+    pub author_windows: HashMap<String, String>,
+    /// Regexes that find tickets in commit titles and branch names.
+    pub ticket_patterns: Vec<String>,
+    /// The hourly rates of repos or clients in timesheets.
+    pub rates: HashMap<String, f64>,
+    /// The clients of repos in timesheets, by the label of the repo.
+    pub clients: HashMap<String, String>,
+}
+
+impl Config {
+    /// Reads a config file from a path.
+    pub fn from_path(path: &str) -> Result<Self> {
+        let data = fs::read_to_string(path).with_context(|| format!("Can't read config file: '{path}'"))?;
+        return serde_json::from_str(&data).with_context(|| format!("Can't parse config file: '{path}'"));
+    }
+}
+
+/// A repository listed in a workspace file.
+#[derive(Deserialize, Debug, Clone)]
+pub struct WorkspaceRepository {
+    /// The path to the repository, relative to the workspace file.
+    pub path: String,
+    /// The label of the repository in the report, the name of its directory if not set.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// The repositories passed together with `--workspace`.
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct Workspace {
+    /// The repositories of the workspace.
+    pub repositories: Vec<WorkspaceRepository>,
+}
+
+impl Workspace {
+    /// Reads a workspace file from a path, making the paths of its repositories relative to the
+    /// current directory.
+    pub fn from_path(path: &str) -> Result<Self> {
+        let data = fs::read_to_string(path).with_context(|| format!("Can't read workspace file: '{path}'"))?;
+        let mut workspace: Self = serde_json::from_str(&data).with_context(|| format!("Can't parse workspace file: '{path}'"))?;
+
+        let root = Path::new(path).parent().unwrap_or(Path::new(""));
+        for repository in workspace.repositories.iter_mut() {
+            repository.path = root.join(&repository.path).to_string_lossy().to_string();
+        }
+        return Ok(workspace);
+    }
+}
@@ -0,0 +1,38 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use git_stats::macros::ok_or_continue;
+
+/// The directory names skipped while scanning by default.
+pub const DEFAULT_SKIP: [&str; 2] = ["node_modules", "target"];
+
+/// Finds every git repo under `root`, looking at most `max_depth` directories deep and never
+/// looking in directories named in `skip`. Repos inside of other repos aren't searched for.
+pub fn find_repositories(root: &Path, max_depth: usize, skip: &[String]) -> Vec<PathBuf> {
+    let mut repositories: Vec<PathBuf> = vec![];
+    let mut directories: Vec<(PathBuf, usize)> = vec![(root.to_path_buf(), 0)];
+
+    while let Some((directory, depth)) = directories.pop() {
+        if directory.join(".git").is_dir() {
+            repositories.push(directory);
+            continue;
+        }
+        if depth >= max_depth {
+            continue;
+        }
+
+        for entry in ok_or_continue!(fs::read_dir(&directory)) {
+            let path = ok_or_continue!(entry).path();
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            if !path.is_dir() || path.is_symlink() || name == ".git" || skip.contains(&name) {
+                continue;
+            }
+            directories.push((path, depth + 1));
+        }
+    }
+
+    repositories.sort();
+    return repositories;
+}
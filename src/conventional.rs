@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{report::OutputValue, session::WorkSession};
+
+/// The parts of a commit subject written as a Conventional Commit (such as `feat(api)!: ...`.)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConventionalCommit {
+    /// The type of the change (such as `feat` or `fix`), always lowercase.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The scope of the change in parentheses after the type.
+    pub scope: Option<String>,
+    /// If the change is marked as breaking with a `!` before the colon.
+    pub breaking: bool,
+}
+
+impl ConventionalCommit {
+    /// Parses a commit subject, `None` if it isn't a Conventional Commit.
+    /// ```
+    /// # use git_stats::conventional::ConventionalCommit;
+    /// let commit = ConventionalCommit::parse("feat(api)!: Drop the v1 endpoints").unwrap();
+    /// assert_eq!(commit.kind, "feat");
+    /// assert_eq!(commit.scope.unwrap(), "api");
+    /// assert!(commit.breaking);
+    ///
+    /// assert!(ConventionalCommit::parse("Fix: the parser").unwrap().scope.is_none());
+    /// assert!(ConventionalCommit::parse("Merge branch 'feature'").is_none());
+    /// ```
+    pub fn parse(subject: &str) -> Option<Self> {
+        let re = Regex::new(r"^(?<type>[A-Za-z]+)(\((?<scope>[^()]*)\))?(?<breaking>!)?: +\S").unwrap();
+        let capture = re.captures(subject.trim())?;
+
+        return Some(Self {
+            kind: capture.name("type").unwrap().as_str().to_lowercase(),
+            scope: capture
+                .name("scope")
+                .map(|v| v.as_str().trim().to_string())
+                .filter(|v| !v.is_empty()),
+            breaking: capture.name("breaking").is_some(),
+        });
+    }
+}
+
+/// The totals of the commits of a type or scope.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CategorySummary {
+    /// The type or scope, `None` for the commits without one.
+    pub name: Option<String>,
+    /// The amount of commits.
+    pub commits: usize,
+    /// The amount of breaking commits.
+    pub breaking: usize,
+    /// The seconds spent on the commits.
+    pub seconds: u64,
+    /// The fraction of the seconds of every commit spent on these commits.
+    pub share: f32,
+}
+
+/// The time spent on every Conventional Commit type and scope.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ConventionalSummary {
+    /// The totals of every type, the most seconds first.
+    pub types: Vec<CategorySummary>,
+    /// The totals of every scope, the most seconds first.
+    pub scopes: Vec<CategorySummary>,
+}
+
+impl ConventionalSummary {
+    /// Totals the commits of sessions (along with their values in the same order) by their type
+    /// and scope.
+    pub fn from_sessions(sessions: &[WorkSession], values: &[Vec<OutputValue>]) -> Self {
+        let mut types: HashMap<Option<String>, CategorySummary> = HashMap::new();
+        let mut scopes: HashMap<Option<String>, CategorySummary> = HashMap::new();
+
+        for (session, values) in sessions.iter().zip(values) {
+            for (commit, value) in session.commits.iter().zip(values) {
+                for (categories, name) in [(&mut types, &value.commit_type), (&mut scopes, &value.scope)] {
+                    let summary = categories.entry(name.clone()).or_insert_with(|| CategorySummary {
+                        name: name.clone(),
+                        ..Default::default()
+                    });
+                    summary.commits += 1;
+                    summary.breaking += value.breaking as usize;
+                    summary.seconds += commit.seconds;
+                }
+            }
+        }
+
+        return Self {
+            types: Self::sorted(types),
+            scopes: Self::sorted(scopes),
+        };
+    }
+
+    /// Sorts the totals by the most seconds first and fills in their share.
+    fn sorted(categories: HashMap<Option<String>, CategorySummary>) -> Vec<CategorySummary> {
+        let total = categories.values().map(|v| v.seconds).sum::<u64>().max(1);
+        let mut categories = categories
+            .into_values()
+            .map(|mut v| {
+                v.share = v.seconds as f32 / total as f32;
+                return v;
+            })
+            .collect::<Vec<CategorySummary>>();
+        categories.sort_by(|a, b| b.seconds.cmp(&a.seconds).then(a.name.cmp(&b.name)));
+        return categories;
+    }
+}
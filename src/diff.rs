@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::objects::{
+    blob::BlobKind,
+    tree::{TreeFile, TreeItemMode},
+};
+
+/// The difference between two flattened trees.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TreeDiff {
+    /// The amount of lines removed.
+    pub lines_removed: i32,
+    /// The amount of lines added.
+    pub lines_added: i32,
+    /// The amount of lines removed from generated, vendored or documentation files.
+    pub ignored_lines_removed: i32,
+    /// The amount of lines added to generated, vendored or documentation files.
+    pub ignored_lines_added: i32,
+    /// Paths which changed mode but kept the same contents (such as `chmod +x`.)
+    pub mode_changes: Vec<String>,
+    /// Symlink paths that now point somewhere else.
+    pub symlink_retargets: Vec<String>,
+    /// The amount of bytes removed from binary files.
+    pub binary_bytes_removed: u64,
+    /// The amount of bytes added to binary files.
+    pub binary_bytes_added: u64,
+    /// Paths of Git LFS pointers that now point to a different object.
+    pub lfs_changes: Vec<String>,
+    /// The amount of bytes removed from objects stored in Git LFS.
+    pub lfs_bytes_removed: u64,
+    /// The amount of bytes added to objects stored in Git LFS.
+    pub lfs_bytes_added: u64,
+}
+
+impl TreeDiff {
+    /// Gets the lines removed with the binary and LFS bytes removed counted as
+    /// `binary_weight` and `lfs_weight` lines per KiB.
+    pub fn weighted_lines_removed(&self, binary_weight: f32, lfs_weight: f32) -> f32 {
+        return self.lines_removed as f32 +
+            self.binary_bytes_removed as f32 / 1024.0 * binary_weight +
+            self.lfs_bytes_removed as f32 / 1024.0 * lfs_weight;
+    }
+
+    /// Gets the lines added with the binary and LFS bytes added counted as
+    /// `binary_weight` and `lfs_weight` lines per KiB.
+    pub fn weighted_lines_added(&self, binary_weight: f32, lfs_weight: f32) -> f32 {
+        return self.lines_added as f32 +
+            self.binary_bytes_added as f32 / 1024.0 * binary_weight +
+            self.lfs_bytes_added as f32 / 1024.0 * lfs_weight;
+    }
+}
+
+/// Subtracts the values of tree1 from tree2.
+/// Includes all the hashes from both trees combined.
+/// The first tree is the newest tree.
+/// The second tree is the previous tree.
+/// Mode-only changes, symlink retargets, binary size changes and LFS object changes are reported
+/// separately from line changes.
+/// Lines of files marked as generated, vendored or documentation by `.gitattributes` are counted
+/// as ignored lines instead.
+/// ```
+/// # use std::collections::HashMap;
+/// # use git_stats::diff::tree_diff;
+/// # use git_stats::objects::{blob::{BlobKind, BlobSummary}, tree::{TreeFile, TreeItemMode}};
+/// let file = |oid: &str, lines: u32| TreeFile::new(
+///     TreeItemMode::File,
+///     oid.into(),
+///     BlobSummary { kind: BlobKind::Text, lines, size: 0 },
+/// );
+///
+/// let old_tree = HashMap::from([("main.rs".to_string(), file("a", 10))]);
+/// let new_tree = HashMap::from([
+///     ("main.rs".to_string(), file("b", 4)),
+///     ("lib.rs".to_string(), file("c", 20)),
+/// ]);
+///
+/// let diff = tree_diff(new_tree, old_tree);
+/// assert_eq!(diff.lines_removed, 6);
+/// assert_eq!(diff.lines_added, 20);
+/// ```
+pub fn tree_diff(current_tree: HashMap<String, TreeFile>, old_tree: HashMap<String, TreeFile>) -> TreeDiff {
+    let mut all_keys = current_tree
+        .keys()
+        .collect::<Vec<&String>>();
+    all_keys.extend(old_tree.keys().filter(|k| !current_tree.contains_key(*k)));
+
+    let mut diff = TreeDiff::default();
+
+    for key in all_keys {
+        let new_file = current_tree.get(key);
+        let old_file = old_tree.get(key);
+
+        if let (Some(new_file), Some(old_file)) = (new_file, old_file) {
+            if new_file.oid == old_file.oid {
+                if new_file.mode != old_file.mode {
+                    diff.mode_changes.push(key.to_owned());
+                }
+                continue;
+            }
+            if new_file.mode == TreeItemMode::Symlink && old_file.mode == TreeItemMode::Symlink {
+                diff.symlink_retargets.push(key.to_owned());
+                continue;
+            }
+        }
+
+        let is_binary = |file: Option<&TreeFile>| file.is_some_and(|v| v.blob.kind == BlobKind::Binary);
+        if is_binary(new_file) || is_binary(old_file) {
+            let new_size = new_file.filter(|v| is_binary(Some(v))).map(|v| v.blob.size).unwrap_or(0);
+            let old_size = old_file.filter(|v| is_binary(Some(v))).map(|v| v.blob.size).unwrap_or(0);
+
+            if new_size > old_size {
+                diff.binary_bytes_added += new_size - old_size;
+            } else {
+                diff.binary_bytes_removed += old_size - new_size;
+            }
+        }
+
+        let lfs_size = |file: Option<&TreeFile>| match file.map(|v| &v.blob.kind) {
+            Some(BlobKind::LfsPointer(pointer)) => Some(pointer.size),
+            _ => None,
+        };
+        if lfs_size(new_file).is_some() || lfs_size(old_file).is_some() {
+            let new_size = lfs_size(new_file).unwrap_or(0);
+            let old_size = lfs_size(old_file).unwrap_or(0);
+
+            diff.lfs_changes.push(key.to_owned());
+            if new_size > old_size {
+                diff.lfs_bytes_added += new_size - old_size;
+            } else {
+                diff.lfs_bytes_removed += old_size - new_size;
+            }
+        }
+
+        let new_value = new_file.map(|v| v.blob.lines).unwrap_or(0);
+        let old_value = old_file.map(|v| v.blob.lines).unwrap_or(0);
+
+        let delta = new_value as i32 - old_value as i32;
+
+        let is_ignored = new_file.or(old_file).is_some_and(|v| v.linguist.is_some());
+        if is_ignored {
+            if delta > 0 {
+                diff.ignored_lines_added += delta;
+            } else {
+                diff.ignored_lines_removed -= delta;
+            }
+        } else if delta > 0 {
+            diff.lines_added += delta;
+        } else {
+            diff.lines_removed -= delta;
+        }
+    }
+
+    return diff;
+}
+
+/// Compares a merge commit's tree to the trees of its parents.
+/// Only files that don't match the version of any parent (such as resolved conflicts) are
+/// counted, compared to the first parent, since everything else was already counted in the commits
+/// of the branches being merged.
+/// ```
+/// # use std::collections::HashMap;
+/// # use git_stats::diff::merge_diff;
+/// # use git_stats::objects::{blob::{BlobKind, BlobSummary}, tree::{TreeFile, TreeItemMode}};
+/// let file = |oid: &str, lines: u32| TreeFile::new(
+///     TreeItemMode::File,
+///     oid.into(),
+///     BlobSummary { kind: BlobKind::Text, lines, size: 0 },
+/// );
+///
+/// let first = HashMap::from([("a.rs".to_string(), file("a1", 10)), ("b.rs".to_string(), file("b1", 10))]);
+/// let second = HashMap::from([("a.rs".to_string(), file("a2", 20)), ("b.rs".to_string(), file("b2", 10))]);
+///
+/// // `a.rs` is taken from the second branch while `b.rs` had a conflict
+/// let merge = HashMap::from([("a.rs".to_string(), file("a2", 20)), ("b.rs".to_string(), file("b3", 15))]);
+///
+/// let diff = merge_diff(merge, vec![first, second]);
+/// assert_eq!(diff.lines_added, 5);
+/// ```
+pub fn merge_diff(current_tree: HashMap<String, TreeFile>, parent_trees: Vec<HashMap<String, TreeFile>>) -> TreeDiff {
+    let mut parent_trees = parent_trees.into_iter();
+    let first_tree = parent_trees.next().unwrap_or_default();
+    let other_trees = parent_trees.collect::<Vec<HashMap<String, TreeFile>>>();
+
+    let mut all_keys = current_tree.keys().collect::<Vec<&String>>();
+    all_keys.extend(first_tree.keys().filter(|k| !current_tree.contains_key(*k)));
+
+    let oid = |tree: &HashMap<String, TreeFile>, key: &str| tree.get(key).map(|v| v.oid.to_owned());
+
+    let mut current_files = HashMap::new();
+    let mut first_files = HashMap::new();
+    for key in all_keys {
+        let current_oid = oid(&current_tree, key);
+        if other_trees.iter().any(|v| oid(v, key) == current_oid) {
+            continue;
+        }
+
+        if let Some(v) = current_tree.get(key) {
+            current_files.insert(key.to_owned(), v.to_owned());
+        }
+        if let Some(v) = first_tree.get(key) {
+            first_files.insert(key.to_owned(), v.to_owned());
+        }
+    }
+
+    return tree_diff(current_files, first_files);
+}
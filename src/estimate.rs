@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+
+use crate::stats::{DroppedSamples, RateStatistics, DEFAULT_TRIM};
+
+/// A commit as seen by an [`Estimator`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EstimatorSample {
+    /// The (weighted) amount of lines removed by the commit.
+    pub lines_removed: f32,
+    /// The (weighted) amount of lines added by the commit.
+    pub lines_added: f32,
+    /// The seconds between the commit and its parent.
+    pub seconds: i64,
+    /// The identity of the author of the commit (see [`crate::objects::commit::CommitAuthor::identity`].)
+    pub author: String,
+}
+
+impl EstimatorSample {
+    /// Creates a new sample.
+    pub fn new(lines_removed: f32, lines_added: f32, seconds: i64, author: String) -> Self {
+        return Self {
+            lines_removed,
+            lines_added,
+            seconds,
+            author,
+        };
+    }
+}
+
+/// Projects how long the first commit of a work session took.
+/// Since the start of a session isn't recorded anywhere, this has to be guessed.
+pub trait Estimator {
+    /// Learns from all the commits that were walked.
+    /// Returns the samples that were left out while learning.
+    fn fit(&mut self, samples: &[EstimatorSample]) -> DroppedSamples;
+
+    /// Estimates the amount of seconds spent on the first commit of a session.
+    fn estimate(&self, sample: &EstimatorSample) -> u64;
+
+    /// Whether fitting found anything to project from. When it didn't (such as when no commit was
+    /// measured within the window) every estimate would be 0, so a fallback is used instead.
+    fn has_data(&self) -> bool {
+        return true;
+    }
+}
+
+/// Projects the time of a commit from the lines removed and added per second, the average of the
+/// time the removed lines and the added lines would take. Rates that are unknown (zero) are left
+/// out.
+fn project(sample: &EstimatorSample, removed_rate: f32, added_rate: f32) -> u64 {
+    let projections = [(sample.lines_removed, removed_rate), (sample.lines_added, added_rate)]
+        .into_iter()
+        .filter(|(_, rate)| *rate > 0.0)
+        .map(|(lines, rate)| lines / rate)
+        .collect::<Vec<f32>>();
+    if projections.is_empty() {
+        return 0;
+    }
+    return (projections.iter().sum::<f32>() / projections.len() as f32) as u64;
+}
+
+/// Gets the statistics of the lines removed and the lines added per second, along with the samples
+/// left out of either of them. Samples without an interval are counted once, rates that aren't
+/// finite or were trimmed are counted for each statistic.
+fn rate_statistics(samples: &[EstimatorSample], trim: f32) -> (RateStatistics, RateStatistics, DroppedSamples) {
+    let mut dropped = DroppedSamples::default();
+    let samples = samples
+        .iter()
+        .filter(|v| {
+            if v.seconds <= 0 {
+                dropped.non_positive_interval += 1;
+            }
+            return v.seconds > 0;
+        })
+        .collect::<Vec<&EstimatorSample>>();
+
+    let removed = RateStatistics::new(samples.iter().map(|v| (v.lines_removed, v.seconds)), trim);
+    let added = RateStatistics::new(samples.iter().map(|v| (v.lines_added, v.seconds)), trim);
+    dropped += removed.dropped;
+    dropped += added.dropped;
+    return (removed, added, dropped);
+}
+
+/// Uses the (trimmed) average lines removed and added per second of every commit.
+/// ```
+/// # use git_stats::estimate::{AverageEstimator, Estimator, EstimatorSample};
+/// let mut estimator = AverageEstimator::default();
+/// estimator.fit(&[
+///     EstimatorSample::new(50.0, 100.0, 100, "a".into()),
+///     EstimatorSample::new(50.0, 300.0, 100, "a".into()),
+/// ]);
+/// // 0.5 lines removed and 2 lines added per second on average
+/// assert_eq!(estimator.estimate(&EstimatorSample::new(0.0, 100.0, 0, "a".into())), 25);
+/// assert_eq!(estimator.estimate(&EstimatorSample::new(10.0, 100.0, 0, "a".into())), 35);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AverageEstimator {
+    trim: f32,
+    removed_rate: f32,
+    added_rate: f32,
+}
+
+impl AverageEstimator {
+    /// Creates an estimator that leaves `trim` of the rates on each end out of the average.
+    pub fn new(trim: f32) -> Self {
+        return Self {
+            trim,
+            removed_rate: 0.0,
+            added_rate: 0.0,
+        };
+    }
+}
+
+impl Default for AverageEstimator {
+    fn default() -> Self {
+        return Self::new(DEFAULT_TRIM);
+    }
+}
+
+impl Estimator for AverageEstimator {
+    fn fit(&mut self, samples: &[EstimatorSample]) -> DroppedSamples {
+        let (removed, added, dropped) = rate_statistics(samples, self.trim);
+        self.removed_rate = removed.mean();
+        self.added_rate = added.mean();
+        return dropped;
+    }
+
+    fn estimate(&self, sample: &EstimatorSample) -> u64 {
+        return project(sample, self.removed_rate, self.added_rate);
+    }
+
+    fn has_data(&self) -> bool {
+        return self.removed_rate > 0.0 || self.added_rate > 0.0;
+    }
+}
+
+/// Uses the median lines removed and added per second, which is less affected by outliers.
+/// ```
+/// # use git_stats::estimate::{MedianEstimator, Estimator, EstimatorSample};
+/// let mut estimator = MedianEstimator::default();
+/// estimator.fit(&[
+///     EstimatorSample::new(0.0, 100.0, 100, "a".into()),
+///     EstimatorSample::new(0.0, 200.0, 100, "a".into()),
+///     EstimatorSample::new(0.0, 90000.0, 100, "a".into()),
+/// ]);
+/// // 2 lines per second, the outlier is ignored
+/// assert_eq!(estimator.estimate(&EstimatorSample::new(0.0, 100.0, 0, "a".into())), 50);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MedianEstimator {
+    removed_rate: f32,
+    added_rate: f32,
+}
+
+impl Estimator for MedianEstimator {
+    fn fit(&mut self, samples: &[EstimatorSample]) -> DroppedSamples {
+        let (removed, added, dropped) = rate_statistics(samples, 0.0);
+        self.removed_rate = removed.median();
+        self.added_rate = added.median();
+        return dropped;
+    }
+
+    fn estimate(&self, sample: &EstimatorSample) -> u64 {
+        return project(sample, self.removed_rate, self.added_rate);
+    }
+
+    fn has_data(&self) -> bool {
+        return self.removed_rate > 0.0 || self.added_rate > 0.0;
+    }
+}
+
+/// Uses the average rates of the author of the commit.
+/// Falls back to the average of everyone for authors it hasn't seen or has no rates for.
+/// ```
+/// # use git_stats::estimate::{PerAuthorEstimator, Estimator, EstimatorSample};
+/// let mut estimator = PerAuthorEstimator::default();
+/// estimator.fit(&[
+///     EstimatorSample::new(0.0, 300.0, 100, "fast".into()),
+///     EstimatorSample::new(0.0, 100.0, 100, "slow".into()),
+/// ]);
+/// assert_eq!(estimator.estimate(&EstimatorSample::new(0.0, 300.0, 0, "fast".into())), 100);
+/// assert_eq!(estimator.estimate(&EstimatorSample::new(0.0, 300.0, 0, "slow".into())), 300);
+/// assert_eq!(estimator.estimate(&EstimatorSample::new(0.0, 300.0, 0, "new".into())), 150);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PerAuthorEstimator {
+    authors: HashMap<String, AverageEstimator>,
+    everyone: AverageEstimator,
+}
+
+impl PerAuthorEstimator {
+    /// Creates an estimator that leaves `trim` of the rates on each end out of the averages.
+    pub fn new(trim: f32) -> Self {
+        return Self {
+            authors: HashMap::new(),
+            everyone: AverageEstimator::new(trim),
+        };
+    }
+}
+
+impl Estimator for PerAuthorEstimator {
+    fn fit(&mut self, samples: &[EstimatorSample]) -> DroppedSamples {
+        let dropped = self.everyone.fit(samples);
+
+        let mut by_author: HashMap<String, Vec<EstimatorSample>> = HashMap::new();
+        for sample in samples {
+            by_author.entry(sample.author.clone()).or_default().push(sample.clone());
+        }
+
+        self.authors = by_author
+            .into_iter()
+            .map(|(author, samples)| {
+                let mut estimator = self.everyone.clone();
+                estimator.fit(&samples);
+                return (author, estimator);
+            })
+            .collect();
+
+        return dropped;
+    }
+
+    fn estimate(&self, sample: &EstimatorSample) -> u64 {
+        return match self.authors.get(&sample.author) {
+            Some(v) if v.has_data() => v.estimate(sample),
+            _ => self.everyone.estimate(sample),
+        };
+    }
+
+    fn has_data(&self) -> bool {
+        return self.everyone.has_data();
+    }
+}
+
+/// Gives every session the same fixed amount of seconds before its first commit.
+/// ```
+/// # use git_stats::estimate::{FixedEstimator, Estimator, EstimatorSample};
+/// let estimator = FixedEstimator(1800);
+/// assert_eq!(estimator.estimate(&EstimatorSample::new(0.0, 100.0, 0, "a".into())), 1800);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FixedEstimator(pub u64);
+
+impl Estimator for FixedEstimator {
+    fn fit(&mut self, _samples: &[EstimatorSample]) -> DroppedSamples {
+        return DroppedSamples::default();
+    }
+
+    fn estimate(&self, _sample: &EstimatorSample) -> u64 {
+        return self.0;
+    }
+}
+
+/// Uses the usual gap between the commits of the author within their sessions (the median), capped
+/// to a maximum. The gap before the first commit of a session is always longer than the window,
+/// so the gaps between the other commits of the sessions are used instead.
+/// Authors without any such gaps get the maximum.
+/// ```
+/// # use git_stats::estimate::{GapCappedEstimator, Estimator, EstimatorSample};
+/// let mut estimator = GapCappedEstimator::new(3600);
+/// estimator.fit(&[
+///     EstimatorSample::new(0.0, 100.0, 600, "quick".into()),
+///     EstimatorSample::new(0.0, 100.0, 900, "quick".into()),
+///     EstimatorSample::new(0.0, 100.0, 1200, "quick".into()),
+///     EstimatorSample::new(0.0, 100.0, 7200, "slow".into()),
+/// ]);
+/// assert_eq!(estimator.estimate(&EstimatorSample::new(0.0, 100.0, 86400, "quick".into())), 900);
+/// assert_eq!(estimator.estimate(&EstimatorSample::new(0.0, 100.0, 86400, "slow".into())), 3600);
+/// assert_eq!(estimator.estimate(&EstimatorSample::new(0.0, 100.0, 0, "new".into())), 3600);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GapCappedEstimator {
+    max: u64,
+    gaps: HashMap<String, u64>,
+}
+
+impl GapCappedEstimator {
+    /// Creates an estimator that never projects more than `max` seconds.
+    pub fn new(max: u64) -> Self {
+        return Self {
+            max,
+            gaps: HashMap::new(),
+        };
+    }
+}
+
+impl Estimator for GapCappedEstimator {
+    fn fit(&mut self, samples: &[EstimatorSample]) -> DroppedSamples {
+        let mut dropped = DroppedSamples::default();
+        let mut by_author: HashMap<String, Vec<u64>> = HashMap::new();
+        for sample in samples {
+            if sample.seconds <= 0 {
+                dropped.non_positive_interval += 1;
+                continue;
+            }
+            by_author.entry(sample.author.clone()).or_default().push(sample.seconds as u64);
+        }
+
+        self.gaps = by_author
+            .into_iter()
+            .map(|(author, mut gaps)| {
+                gaps.sort_unstable();
+                return (author, gaps[gaps.len() / 2]);
+            })
+            .collect();
+        return dropped;
+    }
+
+    fn estimate(&self, sample: &EstimatorSample) -> u64 {
+        return self.gaps.get(&sample.author).map_or(self.max, |v| (*v).min(self.max));
+    }
+}
+
+/// Keeps the estimates of another estimator between a minimum and a maximum amount of seconds.
+/// ```
+/// # use git_stats::estimate::{ClampedEstimator, FixedEstimator, Estimator, EstimatorSample};
+/// let estimator = ClampedEstimator::new(Box::new(FixedEstimator(10)), Some(900), None);
+/// assert_eq!(estimator.estimate(&EstimatorSample::new(0.0, 100.0, 0, "a".into())), 900);
+/// ```
+pub struct ClampedEstimator {
+    inner: Box<dyn Estimator>,
+    min: Option<u64>,
+    max: Option<u64>,
+}
+
+impl ClampedEstimator {
+    /// Wraps an estimator with a minimum and maximum.
+    pub fn new(inner: Box<dyn Estimator>, min: Option<u64>, max: Option<u64>) -> Self {
+        return Self {
+            inner,
+            min,
+            max,
+        };
+    }
+}
+
+impl Estimator for ClampedEstimator {
+    fn fit(&mut self, samples: &[EstimatorSample]) -> DroppedSamples {
+        return self.inner.fit(samples);
+    }
+
+    fn estimate(&self, sample: &EstimatorSample) -> u64 {
+        let estimate = self.inner.estimate(sample);
+        let estimate = self.min.map_or(estimate, |v| estimate.max(v));
+        return self.max.map_or(estimate, |v| estimate.min(v));
+    }
+
+    fn has_data(&self) -> bool {
+        return self.inner.has_data();
+    }
+}
@@ -0,0 +1,142 @@
+use anyhow::Result;
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::{report::OutputValue, session::WorkSession};
+
+/// The columns of a [`FlatRow`] in the order they are written.
+pub const COLUMNS: [&str; 11] = [
+    "oid", "repo", "author", "title", "start", "end", "duration", "lines_removed", "lines_added", "estimated", "commits",
+];
+
+/// Quotes a CSV field if it has to be.
+pub(crate) fn csv_field(field: &str) -> String {
+    if !field.contains([',', '"', '\n', '\r']) {
+        return field.to_string();
+    }
+    return format!("\"{}\"", field.replace('"', "\"\""));
+}
+
+/// A commit or session as a row of flat values with stable names, for spreadsheets and line based
+/// tools.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct FlatRow {
+    /// The oid of the commit, or the newest commit of the session.
+    pub oid: String,
+    /// The label of the repository.
+    pub repo: String,
+    /// The identity of the author.
+    pub author: String,
+    /// The title of the commit, or the titles of the commits of the session oldest first.
+    pub title: String,
+    /// The (projected) time the work started at (RFC 3339.)
+    pub start: String,
+    /// The time the work ended at (RFC 3339.)
+    pub end: String,
+    /// The seconds spent.
+    pub duration: u64,
+    /// The lines removed.
+    pub lines_removed: i64,
+    /// The lines added.
+    pub lines_added: i64,
+    /// If the seconds were projected instead of measured, for sessions if their first commit was.
+    pub estimated: bool,
+    /// The amount of commits, always 1 for commits.
+    pub commits: usize,
+}
+
+impl FlatRow {
+    /// Creates a row for every commit of the sessions (along with their values in the same order.)
+    pub fn commits(sessions: &[WorkSession], values: &[Vec<OutputValue>]) -> Vec<Self> {
+        return sessions
+            .iter()
+            .zip(values)
+            .flat_map(|(session, values)| session.commits.iter().zip(values))
+            .map(|(commit, value)| Self {
+                oid: commit.oid.clone(),
+                repo: value.repo.clone(),
+                author: commit.author.clone(),
+                title: commit.title.clone(),
+                start: value.start.clone(),
+                end: value.end.clone(),
+                duration: commit.seconds,
+                lines_removed: value.lines_removed as i64,
+                lines_added: value.lines_added as i64,
+                estimated: commit.estimated,
+                commits: 1,
+            })
+            .collect();
+    }
+
+    /// Creates a row for every session (along with the values of its commits in the same order.)
+    pub fn sessions(sessions: &[WorkSession], values: &[Vec<OutputValue>]) -> Vec<Self> {
+        let time = |timestamp: i64| DateTime::from_timestamp(timestamp, 0).unwrap_or_default().to_rfc3339();
+
+        return sessions
+            .iter()
+            .zip(values)
+            .map(|(session, values)| Self {
+                oid: session.commits.first().map(|v| v.oid.clone()).unwrap_or_default(),
+                repo: session.repo.clone(),
+                author: session.author.clone(),
+                title: session.commits
+                    .iter()
+                    .rev()
+                    .map(|v| v.title.as_str())
+                    .collect::<Vec<&str>>()
+                    .join("; "),
+                start: time(session.start),
+                end: time(session.end),
+                duration: session.duration(),
+                lines_removed: values.iter().map(|v| v.lines_removed as i64).sum(),
+                lines_added: values.iter().map(|v| v.lines_added as i64).sum(),
+                estimated: session.commits.last().is_some_and(|v| v.estimated),
+                commits: session.commits.len(),
+            })
+            .collect();
+    }
+
+    /// Gets the values of the row in the order of [`COLUMNS`].
+    pub fn fields(&self) -> [String; COLUMNS.len()] {
+        return [
+            self.oid.clone(),
+            self.repo.clone(),
+            self.author.clone(),
+            self.title.clone(),
+            self.start.clone(),
+            self.end.clone(),
+            self.duration.to_string(),
+            self.lines_removed.to_string(),
+            self.lines_added.to_string(),
+            self.estimated.to_string(),
+            self.commits.to_string(),
+        ];
+    }
+}
+
+/// Writes rows as CSV with a header of the [`COLUMNS`].
+/// ```
+/// # use git_stats::export::{to_csv, FlatRow};
+/// let row = FlatRow { oid: "abc".into(), title: "Fix a, b".into(), commits: 1, ..Default::default() };
+/// let csv = to_csv(&[row]);
+/// assert!(csv.starts_with("oid,repo,author,title,start,end,duration"));
+/// assert_eq!(csv.lines().nth(1).unwrap(), "abc,,,\"Fix a, b\",,,0,0,0,false,1");
+/// ```
+pub fn to_csv(rows: &[FlatRow]) -> String {
+    let mut out = COLUMNS.join(",") + "\n";
+    for row in rows {
+        out += &row.fields().iter().map(|v| csv_field(v)).collect::<Vec<String>>().join(",");
+        out += "\n";
+    }
+    return out;
+}
+
+/// Writes rows as JSON Lines, an object on every line.
+pub fn to_ndjson(rows: &[FlatRow]) -> Result<String> {
+    let mut out = String::new();
+    for row in rows {
+        out += &serde_json::to_string(row)?;
+        out += "\n";
+    }
+    return Ok(out);
+}
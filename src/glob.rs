@@ -0,0 +1,103 @@
+use anyhow::Result;
+use regex::Regex;
+
+/// A compiled glob pattern using the wildmatch rules git uses for `.gitattributes` and pathspecs.
+/// `*` and `?` don't match `/` while `**` matches across directories.
+/// ```
+/// # use git_stats::glob::Glob;
+/// let glob = Glob::new("src/**/*.rs").unwrap();
+/// assert!(glob.is_match("src/main.rs"));
+/// assert!(glob.is_match("src/objects/tree.rs"));
+/// assert!(!glob.is_match("src/objects/tree.rs.bak"));
+/// assert!(!glob.is_match("examples/src/main.rs"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Glob {
+    /// The pattern the glob was created from.
+    pub pattern: String,
+    regex: Regex,
+}
+
+impl Glob {
+    /// Compiles a new glob from a pattern.
+    pub fn new(pattern: &str) -> Result<Self> {
+        return Self::compile(pattern, true);
+    }
+
+    /// Compiles a new glob where `*` and `?` also match `/`, like `fnmatch` without
+    /// `FNM_PATHNAME`. This is how git matches pathspecs without the `glob` magic.
+    /// ```
+    /// # use git_stats::glob::Glob;
+    /// let glob = Glob::new_fnmatch("src/*.rs").unwrap();
+    /// assert!(glob.is_match("src/objects/tree.rs"));
+    /// ```
+    pub fn new_fnmatch(pattern: &str) -> Result<Self> {
+        return Self::compile(pattern, false);
+    }
+
+    fn compile(pattern: &str, match_pathname: bool) -> Result<Self> {
+        let (any_chars, any_char) = match match_pathname {
+            true => ("[^/]*", "[^/]"),
+            false => (".*", "."),
+        };
+
+        let mut regex_str = String::from("^");
+        let chars = pattern.chars().collect::<Vec<char>>();
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '*' if match_pathname && chars.get(i + 1) == Some(&'*') => {
+                    let at_start = i == 0 || chars[i - 1] == '/';
+                    let at_end = i + 2 == chars.len();
+                    if at_start && chars.get(i + 2) == Some(&'/') {
+                        // "**/" matches any amount of leading directories
+                        regex_str.push_str("(?:.*/)?");
+                        i += 3;
+                        continue;
+                    } else if at_start && at_end {
+                        regex_str.push_str(".*");
+                    } else {
+                        regex_str.push_str("[^/]*");
+                    }
+                    i += 2;
+                    continue;
+                },
+                '*' => regex_str.push_str(any_chars),
+                '?' => regex_str.push_str(any_char),
+                '[' => {
+                    match chars[i..].iter().skip(1).position(|&v| v == ']') {
+                        Some(end) => {
+                            let class = chars[i + 1..i + 1 + end].iter().collect::<String>();
+                            let class = match class.strip_prefix('!') {
+                                Some(v) => format!("^{}", v),
+                                None => class,
+                            };
+                            regex_str.push_str(&format!("[{}]", class.replace('\\', "\\\\")));
+                            i += end + 2;
+                            continue;
+                        },
+                        None => regex_str.push_str(r"\["),
+                    }
+                },
+                '\\' if i + 1 < chars.len() => {
+                    regex_str.push_str(&regex::escape(&chars[i + 1].to_string()));
+                    i += 1;
+                },
+                c => regex_str.push_str(&regex::escape(&c.to_string())),
+            }
+            i += 1;
+        }
+        regex_str.push('$');
+
+        return Ok(Self {
+            pattern: pattern.to_string(),
+            regex: Regex::new(&regex_str)?,
+        });
+    }
+
+    /// Checks if a path matches the glob.
+    pub fn is_match(&self, path: &str) -> bool {
+        return self.regex.is_match(path);
+    }
+}
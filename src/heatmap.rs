@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+use crate::session::{SessionCommit, WorkSession};
+
+/// The names of the rows of a [`Heatmap`].
+pub const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// The characters used to shade the cells of a rendered heatmap, from no time to the most time.
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// The commits and time worked in every hour of the week, in the local time of each author.
+/// Rows are the days of the week starting on monday and columns are the hours of the day.
+/// ```
+/// # use git_stats::heatmap::Heatmap;
+/// let mut heatmap = Heatmap::default();
+/// // Thursday 1970-01-01 at 00:30 UTC, which is Wednesday at 19:30 at -0500
+/// heatmap.add(1800, -5 * 3600, 3600);
+/// assert_eq!(heatmap.commits[2][19], 1);
+/// assert_eq!(heatmap.minutes[2][18], 30);
+/// assert_eq!(heatmap.minutes[2][19], 30);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Heatmap {
+    /// The amount of commits made in every hour.
+    pub commits: [[usize; 24]; 7],
+    /// The minutes spent in every hour (including projected time.)
+    pub minutes: [[u64; 24]; 7],
+    /// The seconds spent in every hour, kept to round the minutes only once.
+    #[serde(skip)]
+    seconds: [[u64; 24]; 7],
+}
+
+/// Gets the day of the week (monday being 0) and hour of a local unix timestamp.
+fn cell(local: i64) -> (usize, usize) {
+    // 1970-01-01 was a thursday
+    let day = (local.div_euclid(86400) + 3).rem_euclid(7);
+    let hour = local.rem_euclid(86400) / 3600;
+    return (day as usize, hour as usize);
+}
+
+impl Heatmap {
+    /// Creates the heatmap of the commits of every session.
+    pub fn from_sessions(sessions: &[WorkSession]) -> Self {
+        let mut heatmap = Self::default();
+        for commit in sessions.iter().flat_map(|v| &v.commits) {
+            heatmap.add_commit(commit);
+        }
+        return heatmap;
+    }
+
+    /// Adds a commit from a session in the timezone of its author.
+    pub fn add_commit(&mut self, commit: &SessionCommit) {
+        self.add(commit.timestamp, commit.author_offset, commit.seconds);
+    }
+
+    /// Adds a commit made at `timestamp` (with a timezone `offset` in seconds east of UTC) that
+    /// took `seconds`. The time is spread over the hours before the commit.
+    pub fn add(&mut self, timestamp: i64, offset: i32, seconds: u64) {
+        let end = timestamp + offset as i64;
+        let (day, hour) = cell(end);
+        self.commits[day][hour] += 1;
+
+        let mut start = end - seconds as i64;
+        while start < end {
+            let hour_end = (start.div_euclid(3600) + 1) * 3600;
+            let (day, hour) = cell(start);
+            self.seconds[day][hour] += (hour_end.min(end) - start) as u64;
+            self.minutes[day][hour] = (self.seconds[day][hour] + 30) / 60;
+            start = hour_end;
+        }
+    }
+
+    /// Renders the minutes of the heatmap as shaded blocks for a terminal, with a row for every
+    /// day and two columns for every hour.
+    pub fn render(&self) -> String {
+        let most = self.minutes.iter().flatten().max().copied().unwrap_or(0).max(1);
+
+        let mut out = String::from("    ");
+        for hour in (0..24).step_by(3) {
+            out += &format!("{hour:<6}");
+        }
+        out = out.trim_end().to_string() + "\n";
+
+        for (day, name) in WEEKDAYS.iter().enumerate() {
+            out += &format!("{name} ");
+            for minutes in self.minutes[day] {
+                let shade = match minutes {
+                    0 => 0,
+                    v => 1 + (v * (SHADES.len() as u64 - 2) / most) as usize,
+                };
+                out.push(SHADES[shade]);
+                out.push(SHADES[shade]);
+            }
+
+            let hours = self.minutes[day].iter().sum::<u64>() as f32 / 60.0;
+            let commits = self.commits[day].iter().sum::<usize>();
+            out += &format!(" {hours:.1}h {commits} commits\n");
+        }
+        return out;
+    }
+}
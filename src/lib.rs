@@ -25,6 +25,69 @@ pub mod macros;
 
 pub mod packfiles;
 
+/// The timestamps module is for deciding which time a commit happened at and finding commits with
+/// clock problems.
+pub mod timestamps;
+
+/// The attributes module is for reading `.gitattributes` files. This includes the
+/// [`attributes::GitAttributes`] struct.
+pub mod attributes;
+
+/// The estimate module is for projecting when work sessions started. This includes the
+/// [`estimate::Estimator`] trait and its built in implementations.
+pub mod estimate;
+
+/// The stats module is for statistics that are robust against bad samples. This includes the
+/// [`stats::RateStatistics`] struct.
+pub mod stats;
+
+/// The session module is for grouping commits into work sessions. This includes the
+/// [`session::WorkSession`] struct and the [`session::SessionBuilder`] that creates them.
+pub mod session;
+
+/// The diff module is for comparing the flattened trees of two commits. This includes the
+/// [`diff::TreeDiff`] struct.
+pub mod diff;
+
+/// The report module is for walking a repository and creating its statistics. This includes the
+/// [`report::StatsBuilder`] struct.
+pub mod report;
+
+/// The summary module is for totaling sessions by day, week or month. This includes the
+/// [`summary::PeriodSummary`] struct.
+pub mod summary;
+
+/// The heatmap module is for the time worked in every hour of the week. This includes the
+/// [`heatmap::Heatmap`] struct.
+pub mod heatmap;
+
+/// The calendar module is for the days commits were made on over a year. This includes the
+/// [`calendar::ContributionCalendar`] struct.
+pub mod calendar;
+
+/// The conventional module is for reading commit titles written as Conventional Commits. This
+/// includes the [`conventional::ConventionalCommit`] struct.
+pub mod conventional;
+
+/// The tickets module is for finding the tickets commits reference and the time spent on them.
+/// This includes the [`tickets::TicketSummary`] struct.
+pub mod tickets;
+
+/// The billing module is for turning sessions into billable time. This includes the
+/// [`billing::Timesheet`] struct.
+pub mod billing;
+
+/// The export module is for writing commits and sessions as flat rows. This includes the
+/// [`export::FlatRow`] struct.
+pub mod export;
+
+/// The glob module is for matching paths against git style glob patterns.
+pub mod glob;
+
+/// The pathspec module is for filtering which paths count toward statistics. This includes the
+/// [`pathspec::PathFilter`] struct.
+pub mod pathspec;
+
 mod repo;
 
 pub use crate::repo::Repo;
@@ -7,7 +7,86 @@ use super::{
     GitObjectAttributes,
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+
+/// The amount of bytes checked for a NUL byte when guessing if a blob is binary.
+/// This is the same amount git checks.
+const BINARY_CHECK_SIZE: usize = 8000;
+
+/// The first line of every Git LFS pointer file.
+const LFS_VERSION_LINE: &str = "version https://git-lfs.github.com/spec/v1";
+
+/// Git LFS pointer files are never bigger than this.
+const LFS_MAX_POINTER_SIZE: usize = 1024;
+
+/// The kind of content a blob holds.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum BlobKind {
+    /// Text which has its lines counted.
+    #[default]
+    Text,
+    /// Binary data which only has its size counted.
+    Binary,
+    /// A pointer to an object stored in Git LFS.
+    LfsPointer(LfsPointer),
+}
+
+/// The object a Git LFS pointer file points to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LfsPointer {
+    /// The oid of the LFS object, including the hash method (such as `sha256:...`.)
+    pub oid: String,
+    /// The size of the LFS object in bytes.
+    pub size: u64,
+}
+
+impl LfsPointer {
+    /// Parses a Git LFS pointer file.
+    /// Returns None if the data isn't a pointer file.
+    /// ```
+    /// # use git_stats::objects::blob::LfsPointer;
+    /// let pointer = LfsPointer::parse("version https://git-lfs.github.com/spec/v1
+    /// oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393
+    /// size 12345
+    /// ").unwrap();
+    /// assert_eq!(pointer.oid, "sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393");
+    /// assert_eq!(pointer.size, 12345);
+    /// assert!(LfsPointer::parse("fn main() {}").is_none());
+    /// ```
+    pub fn parse(in_str: &str) -> Option<Self> {
+        let mut lines = in_str.lines();
+        if lines.next()? != LFS_VERSION_LINE {
+            return None;
+        }
+
+        let mut oid = None;
+        let mut size = None;
+        for line in lines {
+            match line.split_once(' ') {
+                Some(("oid", v)) => oid = Some(v.to_string()),
+                Some(("size", v)) => size = v.parse().ok(),
+                _ => (),
+            }
+        }
+
+        return Some(Self {
+            oid: oid?,
+            size: size?,
+        });
+    }
+}
+
+/// The information about a blob that is used for statistics.
+/// This is what gets cached in the repo by oid.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BlobSummary {
+    /// The kind of data the blob holds.
+    pub kind: BlobKind,
+    /// The amount of lines in the blob, 0 for binary blobs.
+    pub lines: u32,
+    /// The size of the blob in bytes.
+    pub size: u64,
+}
 
 /// Object that represents a blob.
 #[derive(Debug, Clone)]
@@ -48,22 +127,84 @@ impl BlobObject {
         return String::from_utf8_lossy(&self.data).to_string();
     }
 
+    /// Checks if the blob is binary by looking for a NUL byte in its first 8000 bytes.
+    /// ```
+    /// # use git_stats::objects::blob::BlobObject;
+    /// assert!(BlobObject::new(b"\x89PNG\r\n\x1a\n\0\0".to_vec(), 10, "oid".into()).is_binary());
+    /// assert!(!BlobObject::new(b"fn main() {}\n".to_vec(), 13, "oid".into()).is_binary());
+    /// ```
+    pub fn is_binary(&self) -> bool {
+        return self.data
+            .iter()
+            .take(BINARY_CHECK_SIZE)
+            .any(|&v| v == 0);
+    }
+
     /// Function for getting the amount of lines in a blob object.
-    /// Uses the to_string method and counts newlines.
+    /// Counts newlines, plus one for a last line without a newline at the end.
+    /// Binary blobs (see [`BlobObject::is_binary`]) have no lines so 0 is returned.
+    /// ```
+    /// # use git_stats::objects::blob::BlobObject;
+    /// assert_eq!(BlobObject::new(b"a\nb\n".to_vec(), 4, "oid".into()).line_amnt(), 2);
+    /// assert_eq!(BlobObject::new(b"a\nb".to_vec(), 3, "oid".into()).line_amnt(), 2);
+    /// assert_eq!(BlobObject::new(b"".to_vec(), 0, "oid".into()).line_amnt(), 0);
+    /// assert_eq!(BlobObject::new(b"a\n\0\n".to_vec(), 4, "oid".into()).line_amnt(), 0);
+    /// ```
     pub fn line_amnt(&self) -> u32 {
-        return 1u32 +
-            self.data
-                .iter()
-                .filter(|&v| v == &b'\n')
-                .count() as u32
-            ;
+        if self.is_binary() {
+            return 0;
+        }
+        let newlines = self.data
+            .iter()
+            .filter(|&v| v == &b'\n')
+            .count() as u32;
+        let partial_line = self.data.last().is_some_and(|v| v != &b'\n');
+        return newlines + partial_line as u32;
+    }
+
+    /// Gets the Git LFS object the blob points to if the blob is an LFS pointer file.
+    pub fn lfs_pointer(&self) -> Option<LfsPointer> {
+        if self.data.len() > LFS_MAX_POINTER_SIZE || !self.data.starts_with(LFS_VERSION_LINE.as_bytes()) {
+            return None;
+        }
+        return LfsPointer::parse(&self.to_string());
+    }
+
+    /// Gets the summary of the blob used for statistics.
+    /// LFS pointer files aren't counted as lines.
+    pub fn summary(&self) -> BlobSummary {
+        if let Some(pointer) = self.lfs_pointer() {
+            return BlobSummary {
+                kind: BlobKind::LfsPointer(pointer),
+                lines: 0,
+                size: self.data.len() as u64,
+            };
+        }
+
+        let kind = match self.is_binary() {
+            true => BlobKind::Binary,
+            false => BlobKind::Text,
+        };
+
+        return BlobSummary {
+            kind,
+            lines: self.line_amnt(),
+            size: self.data.len() as u64,
+        };
     }
 }
 
 impl GitObjectAttributes for BlobObject {
     fn from_git_object(git_object: &GitObject) -> Result<Box<Self>> {
-        let (obj_type, obj_size, obj_data) = get_type_size_and_data(&git_object.get_data_as_string()?)?;
+        // The data is split by hand as converting binary blobs to strings would change their bytes.
+        let raw_data = git_object.get_data()?;
+        let header_end = match raw_data.iter().position(|&v| v == 0) {
+            Some(v) => v,
+            None => return Err(anyhow!("Null character not found in blob: '{}'", git_object.oid)),
+        };
+        let (obj_type, obj_size, _) = get_type_size_and_data(&String::from_utf8_lossy(&raw_data[..=header_end]))?;
         assert_eq!(obj_type, "blob");
+        let obj_data = raw_data[header_end + 1..].to_vec();
 
         return Ok(Box::new(Self::new(
             obj_data,
@@ -17,8 +17,10 @@ use super::{
 pub struct CommitObject {
     /// The hash that points to the commits tree object
     pub tree: String,
-    /// The hash that points to the previous commit object
+    /// The hash that points to the previous commit object (the first parent for merges.)
     pub parent: Option<String>,
+    /// The hashes of every parent of the commit, more than one for merges.
+    pub parents: Vec<String>,
     /// The commit's author string
     pub author: CommitAuthor,
     /// The commit's committer string
@@ -27,8 +29,11 @@ pub struct CommitObject {
     pub size: i32,
     /// The oid of the commit object (according to meta data.)
     pub oid: String,
-    /// The message of the commit object.
+    /// The message of the commit object (its first line.)
     pub message: String,
+    /// The rest of the message after the first line (such as trailers like `Closes #42`), empty if
+    /// there is none.
+    pub body: String,
 }
 
 impl CommitObject {
@@ -51,6 +56,26 @@ impl CommitObject {
     /// ".trim(), 9999, "some_sha1_hash".into()).unwrap();
     /// assert_eq!(commit.tree, "some_big_hash");
     /// assert_eq!(commit.committer.name, "MT");
+    ///
+    /// // Merges have more than one parent and signed commits have extra headers
+    /// let commit = CommitObject::from_str("
+    /// tree some_big_hash
+    /// parent first_hash
+    /// parent second_hash
+    /// author MT <some@email.tld> 999999 -0123
+    /// committer MT <some@email.tld> 999999 -0123
+    /// gpgsig -----BEGIN PGP SIGNATURE-----
+    ///  some_signature
+    ///  -----END PGP SIGNATURE-----
+    ///
+    /// Merge branch 'feature'
+    ///
+    /// Refs: ABC-123
+    /// ".trim(), 9999, "some_sha1_hash".into()).unwrap();
+    /// assert_eq!(commit.parent.unwrap(), "first_hash");
+    /// assert_eq!(commit.parents, vec!["first_hash", "second_hash"]);
+    /// assert_eq!(commit.message, "Merge branch 'feature'");
+    /// assert_eq!(commit.body, "Refs: ABC-123");
     /// ```
     pub fn from_str(in_string: &str, size: i32, oid: String) -> Result<Self> {
 
@@ -58,12 +83,16 @@ impl CommitObject {
             return capture.name(name).unwrap().as_str().into();
         }
 
+        // Other headers (such as `gpgsig` or `mergetag`) come after the committer and can span
+        // multiple lines that start with a space
         let re = Regex::new(&[
             r"tree (?<tree>.+?)\n",
-            r"(parent (?<parent>.+?)\n)?",
+            r"(?<parents>(parent .+?\n)*)",
             r"author (?<author>.+?)\n",
             r"committer (?<committer>.+?)\n",
+            r"(.+\n)*",
             r"\n(?<message>.+)",
+            r"(?s:\n(?<body>.*))?",
         ].join("")).unwrap();
 
         let capture = match re.captures(in_string) {
@@ -72,24 +101,29 @@ impl CommitObject {
         };
 
         let tree = get_utf8_from_match_group(&capture, "tree");
-        let parent = match &capture.name("parent") {
-            Some(v) => Some(v.as_str().to_string()),
-            None => None,
-        };
+        let parents = get_utf8_from_match_group(&capture, "parents")
+            .lines()
+            .filter_map(|v| v.strip_prefix("parent "))
+            .map(|v| v.to_string())
+            .collect::<Vec<String>>();
+        let parent = parents.first().cloned();
         let author = CommitAuthor::from_string(
             &get_utf8_from_match_group(&capture, "author"))?;
         let committer = CommitAuthor::from_string(
             &get_utf8_from_match_group(&capture, "committer"))?;
         let message = get_utf8_from_match_group(&capture, "message");
+        let body = capture.name("body").map_or("", |v| v.as_str()).trim().to_string();
 
         return Ok(Self {
             tree,
             parent,
+            parents,
             author,
             committer,
             size,
             oid,
             message,
+            body,
         });
     }
 
@@ -160,6 +194,8 @@ pub struct CommitAuthor {
     pub timestamp: u64,
     /// This is the flag that represents the kind.
     pub kind: String,
+    /// The timezone offset of the timestamp in seconds east of UTC.
+    pub offset: i32,
 }
 
 impl CommitAuthor {
@@ -172,13 +208,14 @@ impl CommitAuthor {
     /// assert_eq!(author.email.unwrap(), "some@email.tld");
     /// assert_eq!(author.timestamp, 999999);
     /// assert_eq!(author.kind, "0123");
+    /// assert_eq!(author.offset, -(3600 + 23 * 60));
     /// ```
     pub fn from_string(in_str: &str) -> Result<Self> {
         let re = Regex::new(&[
             r"(?<name>.+?) ",
             r"(<(?<email>.+?)> )?",
             r"(?<timestamp>\d+?) ",
-            r"-(?<kind>\d{4})",
+            r"(?<sign>[+-])(?<kind>(?<hours>\d{2})(?<minutes>\d{2}))",
         ].join("")).unwrap();
 
         let capture = match re.captures(in_str) {
@@ -191,11 +228,28 @@ impl CommitAuthor {
             None => None,
         };
 
+        let number = |name: &str| capture.name(name).unwrap().as_str().parse::<i32>().unwrap();
+        let offset = number("hours") * 3600 + number("minutes") * 60;
+
         return Ok(Self {
             name: capture.name("name").unwrap().as_str().into(),
             email,
             timestamp: capture.name("timestamp").unwrap().as_str().parse().unwrap(),
             kind: capture.name("kind").unwrap().as_str().into(),
+            offset: match capture.name("sign").unwrap().as_str() {
+                "-" => -offset,
+                _ => offset,
+            },
         });
     }
+
+    /// Gets the identity of the author, the email if there is one or the name otherwise.
+    /// ```
+    /// # use git_stats::objects::commit::CommitAuthor;
+    /// let author = CommitAuthor::from_string("MT <some@email.tld> 999999 -0123").unwrap();
+    /// assert_eq!(author.identity(), "some@email.tld");
+    /// ```
+    pub fn identity(&self) -> &str {
+        return self.email.as_deref().unwrap_or(&self.name);
+    }
 }
@@ -3,15 +3,20 @@ use std::{borrow::Cow, collections::HashMap};
 use anyhow::{anyhow, ensure, Context, Result};
 use log::{debug, warn};
 use regex::bytes::Regex;
+use crate::attributes::{GitAttributes, LinguistAttribute};
 use crate::objects::GitObject;
+use crate::pathspec::PathFilter;
 use crate::Repo;
 
 use crate::macros::ok_or_continue;
 
 use super::{
-    blob::BlobObject, get_type_size_and_data, GitObjectAttributes, GitObjectType
+    blob::{BlobKind, BlobObject, BlobSummary}, get_type_size_and_data, GitObjectAttributes, GitObjectType
 };
 
+/// The name of the files that hold attributes for the paths in a tree.
+const ATTRIBUTES_FILENAME: &str = ".gitattributes";
+
 /// Object that represents a Tree
 /// Designed to be initialized using the [`TreeObject::from_git_object`] function.
 #[derive(Debug, Clone)]
@@ -45,9 +50,29 @@ impl TreeObject {
     /// Creates a file system from from tree object.
     /// `path` is the path to the root of the tree.
     /// Usually a good value for this is nothing (`""`).
-    pub fn recurs_create_tree_line_count(&self, repo: &mut Repo, path: &str) -> HashMap<String, u32> {
+    /// Symlinks and gitlinks are included with a line count of 0 as their blobs aren't source
+    /// files, their oid is kept so that retargets can still be detected.
+    /// Files marked as `binary` or `-diff` by the `.gitattributes` files in the tree are counted
+    /// as binary and files marked with a [`LinguistAttribute`] have it set on their [`TreeFile`].
+    /// Only files matching `filter` are included.
+    pub fn recurs_create_tree_line_count(&self, repo: &mut Repo, path: &str, filter: &PathFilter) -> HashMap<String, TreeFile> {
+        return self.recurs_create_tree_line_count_with_attributes(repo, path, filter, GitAttributes::new());
+    }
+
+    fn recurs_create_tree_line_count_with_attributes(&self, repo: &mut Repo, path: &str, filter: &PathFilter, mut attributes: GitAttributes) -> HashMap<String, TreeFile> {
 
-        let mut fs_map: HashMap<String, u32> = HashMap::new();
+        let mut fs_map: HashMap<String, TreeFile> = HashMap::new();
+
+        // Attributes have to be read before any of the files they apply to.
+        let attributes_item = self.items
+            .iter()
+            .find(|v| v.filename == ATTRIBUTES_FILENAME && v.mode.is_file());
+        if let Some(item) = attributes_item {
+            match GitObject::from_oid(repo, &item.oid).and_then(|v| BlobObject::from_git_object(&v)) {
+                Ok(v) => attributes.add_file(path, &v.to_string()),
+                Err(e) => log::warn!("Error reading attributes file: '{e}'!"),
+            };
+        }
 
         for item in &self.items {
             let filename: String;
@@ -57,8 +82,19 @@ impl TreeObject {
                 filename = format!("{}/{}", path, item.filename);
             }
 
-            if let Some(&v) = repo.get_from_cache(&item.oid) {
-                fs_map.insert((&item.oid).to_owned(), v.to_owned());
+            if item.mode != TreeItemMode::Tree && !filter.is_match(&filename) {
+                continue;
+            }
+
+            // Symlinks only hold a path and gitlinks point to commits in another repo.
+            if item.mode == TreeItemMode::Symlink || item.mode == TreeItemMode::Gitlink {
+                fs_map.insert(filename, TreeFile::new(item.mode, item.oid.clone(), BlobSummary::default()));
+                continue;
+            }
+
+            if let Some(v) = repo.get_from_cache(&item.oid) {
+                let file = apply_attributes(TreeFile::new(item.mode, item.oid.clone(), v.to_owned()), &attributes, &filename);
+                fs_map.insert(filename, file);
                 continue;
             }
 
@@ -73,17 +109,18 @@ impl TreeObject {
             let _ = match git_object.initialize_from_data().unwrap() {
                 GitObjectType::Commit(_) => panic!("Commit found in object tree!"),
                 GitObjectType::Blob(v) => {
-                    let line_amnt = v.line_amnt();
-                    if repo.add_to_cache((&item.oid).to_owned(), line_amnt).is_some() {
+                    let summary = v.summary();
+                    if repo.add_to_cache((&item.oid).to_owned(), summary.clone()).is_some() {
                         log::error!("Item already exists in cache! Item: {}", &item.oid);
                     }
-                    match fs_map.insert(filename, v.line_amnt()) {
+                    let file = apply_attributes(TreeFile::new(item.mode, item.oid.clone(), summary), &attributes, &filename);
+                    match fs_map.insert(filename, file) {
                         Some(_colision_value) => panic!(),
                         None => (),
                     }
                 },
                 GitObjectType::Tree(v) => {
-                    fs_map.extend(v.recurs_create_tree_line_count(repo, &filename));
+                    fs_map.extend(v.recurs_create_tree_line_count_with_attributes(repo, &filename, filter, attributes.clone()));
                     ()
                 },
                 GitObjectType::Tag => {
@@ -103,17 +140,106 @@ impl TreeObject {
     }
 }
 
-/// Object that represents an item in a tree.
-#[derive(Debug, Clone)]
-pub struct TreeItem {
-    /// The type of item the file is
-    /// The control bits are similar to linux fs
+/// Marks a file as binary or generated, vendored or documentation if the attributes of its path say so.
+fn apply_attributes(mut file: TreeFile, attributes: &GitAttributes, path: &str) -> TreeFile {
+    if file.blob.kind == BlobKind::Text && attributes.is_binary(path) {
+        file.blob.kind = BlobKind::Binary;
+        file.blob.lines = 0;
+    }
+    file.linguist = attributes.linguist_attribute(path);
+    return file;
+}
+
+/// A file in a flattened tree, as returned by [`TreeObject::recurs_create_tree_line_count`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeFile {
+    /// The mode of the file in the tree it was found in.
+    pub mode: TreeItemMode,
+    /// The oid of the blob (or commit for gitlinks) the file points to.
+    pub oid: String,
+    /// The summary of the blob, the default summary for symlinks and gitlinks.
+    pub blob: BlobSummary,
+    /// The linguist attribute of the file if it is generated, vendored or documentation.
+    pub linguist: Option<LinguistAttribute>,
+}
+
+impl TreeFile {
+    /// Creates a new tree file.
+    /// ```
+    /// # use git_stats::objects::{blob::BlobSummary, tree::{TreeFile, TreeItemMode}};
+    /// let file = TreeFile::new(TreeItemMode::File, "some_oid".into(), BlobSummary::default());
+    /// assert_eq!(file.blob.lines, 0);
+    /// ```
+    pub fn new(mode: TreeItemMode, oid: String, blob: BlobSummary) -> Self {
+        return Self {
+            mode,
+            oid,
+            blob,
+            linguist: None,
+        };
+    }
+}
+
+/// The kind of item a [`TreeItem`] is, parsed from its mode.
+/// The control bits are similar to linux fs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeItemMode {
     /// 040000: tree
+    Tree,
     /// 100644: for a regular file
+    File,
     /// 100755: executable
+    Executable,
     /// 120000: symlink
+    Symlink,
     /// 160000: gitlink
-    pub mode: i32,
+    Gitlink,
+    /// Any other mode, holds the raw value.
+    Unknown(i32),
+}
+
+impl TreeItemMode {
+    /// Returns the raw mode as it is written in the tree object.
+    /// ```
+    /// # use git_stats::objects::tree::TreeItemMode;
+    /// assert_eq!(TreeItemMode::Executable.as_i32(), 100755);
+    /// assert_eq!(TreeItemMode::from(120000), TreeItemMode::Symlink);
+    /// ```
+    pub fn as_i32(&self) -> i32 {
+        return match self {
+            Self::Tree => 40000,
+            Self::File => 100644,
+            Self::Executable => 100755,
+            Self::Symlink => 120000,
+            Self::Gitlink => 160000,
+            Self::Unknown(v) => *v,
+        };
+    }
+
+    /// Returns true if the item is a blob holding file contents.
+    pub fn is_file(&self) -> bool {
+        return matches!(self, Self::File | Self::Executable);
+    }
+}
+
+impl From<i32> for TreeItemMode {
+    fn from(value: i32) -> Self {
+        return match value {
+            40000 => Self::Tree,
+            100644 => Self::File,
+            100755 => Self::Executable,
+            120000 => Self::Symlink,
+            160000 => Self::Gitlink,
+            v => Self::Unknown(v),
+        };
+    }
+}
+
+/// Object that represents an item in a tree.
+#[derive(Debug, Clone)]
+pub struct TreeItem {
+    /// The type of item the file is, see [`TreeItemMode`].
+    pub mode: TreeItemMode,
     /// The name of the folder the tree item refers to
     pub filename: String,
     /// The OID that points to the data the tree item refers to
@@ -123,7 +249,7 @@ pub struct TreeItem {
 impl TreeItem {
     /// Creates a new tree item.
     /// Generally for internal use only.
-    pub fn new(mode: i32, filename: String, oid: String) -> Self {
+    pub fn new(mode: TreeItemMode, filename: String, oid: String) -> Self {
         return Self {
             mode,
             filename,
@@ -179,7 +305,7 @@ impl GitObjectAttributes for TreeObject {
         let (_, obj_size, _) = get_type_size_and_data(&git_object.get_data_as_string()?)?;
 
         // Initializes Variables
-        let re = Regex::new(r"(?<mode>1?[0-7]{5}) (?<filename>.+?)\x00(?<data>(?-u:.){20})").unwrap();
+        let re = Regex::new(r"(?<mode>1?[0-7]{5}) (?<filename>.+?)\x00(?<data>(?s-u:.){20})").unwrap();
 
         let results: Vec<TreeItem> = re
             .captures_iter(&in_data)
@@ -203,7 +329,7 @@ impl GitObjectAttributes for TreeObject {
                     ;
 
                 return TreeItem::new(
-                    number_value,
+                    TreeItemMode::from(number_value),
                     filename,
                     oid,
                 );
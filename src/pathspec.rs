@@ -0,0 +1,158 @@
+use anyhow::{anyhow, ensure, Result};
+
+use crate::glob::Glob;
+
+/// How a pathspec matches paths.
+#[derive(Debug, Clone)]
+enum PathspecMatcher {
+    /// Only matches the path itself or anything under it.
+    Literal(String),
+    /// Wildmatch glob, `*` doesn't match `/`.
+    Glob(String, Glob),
+    /// The default, `*` matches `/` as well.
+    Fnmatch(String, Glob),
+}
+
+/// A single pathspec using git's pathspec syntax.
+/// Supports the `glob`, `literal`, `icase` and `exclude` magic words (and the `:!` / `:^` short
+/// forms for `exclude`.)
+/// ```
+/// # use git_stats::pathspec::Pathspec;
+/// assert!(Pathspec::new("backend").unwrap().is_match("backend/src/main.rs"));
+/// assert!(Pathspec::new("*.rs").unwrap().is_match("backend/src/main.rs"));
+/// assert!(!Pathspec::new(":(glob)*.rs").unwrap().is_match("backend/src/main.rs"));
+/// assert!(Pathspec::new(":(glob)**/*.rs").unwrap().is_match("backend/src/main.rs"));
+/// assert!(Pathspec::new(":!vendor").unwrap().exclude);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pathspec {
+    /// If paths matching this pathspec should be excluded.
+    pub exclude: bool,
+    icase: bool,
+    matcher: PathspecMatcher,
+}
+
+impl Pathspec {
+    /// Parses a pathspec.
+    pub fn new(spec: &str) -> Result<Self> {
+        let mut exclude = false;
+        let mut icase = false;
+        let mut glob = false;
+        let mut literal = false;
+
+        let mut pattern = spec;
+        if let Some(long_magic) = spec.strip_prefix(":(") {
+            let (magic, rest) = long_magic
+                .split_once(')')
+                .ok_or(anyhow!("Pathspec magic isn't closed in '{spec}'"))?;
+            for word in magic.split(',').map(|v| v.trim()) {
+                match word {
+                    "exclude" => exclude = true,
+                    "icase" => icase = true,
+                    "glob" => glob = true,
+                    "literal" => literal = true,
+                    "top" | "" => (),
+                    _ => return Err(anyhow!("Pathspec magic '{word}' isn't supported in '{spec}'")),
+                }
+            }
+            pattern = rest;
+        } else if let Some(short_magic) = spec.strip_prefix(':') {
+            let magic_len = short_magic
+                .find(|c| !matches!(c, '!' | '^' | '/'))
+                .unwrap_or(short_magic.len());
+            exclude = short_magic[..magic_len].contains(['!', '^']);
+            pattern = short_magic[magic_len..].strip_prefix(':').unwrap_or(&short_magic[magic_len..]);
+        }
+
+        ensure!(!(glob && literal), anyhow!("Pathspec magic 'glob' and 'literal' can't be used together in '{spec}'"));
+
+        let pattern = pattern.trim_start_matches('/').trim_end_matches('/');
+        let pattern = match icase {
+            true => pattern.to_lowercase(),
+            false => pattern.to_string(),
+        };
+
+        let has_wildcards = pattern.contains(['*', '?', '[']);
+        let matcher = if literal || !has_wildcards {
+            PathspecMatcher::Literal(pattern)
+        } else if glob {
+            let compiled = Glob::new(&pattern)?;
+            PathspecMatcher::Glob(pattern, compiled)
+        } else {
+            let compiled = Glob::new_fnmatch(&pattern)?;
+            PathspecMatcher::Fnmatch(pattern, compiled)
+        };
+
+        return Ok(Self {
+            exclude,
+            icase,
+            matcher,
+        });
+    }
+
+    /// Checks if the pathspec matches a path (ignoring if it is an exclude pathspec.)
+    pub fn is_match(&self, path: &str) -> bool {
+        let path = match self.icase {
+            true => path.to_lowercase(),
+            false => path.to_string(),
+        };
+
+        let is_prefix = |prefix: &str| {
+            return prefix.is_empty() ||
+                path == prefix ||
+                path.strip_prefix(prefix).is_some_and(|v| v.starts_with('/'));
+        };
+
+        return match &self.matcher {
+            PathspecMatcher::Literal(prefix) => is_prefix(prefix),
+            PathspecMatcher::Glob(prefix, glob) | PathspecMatcher::Fnmatch(prefix, glob) => {
+                is_prefix(prefix) || glob.is_match(&path)
+            },
+        };
+    }
+}
+
+/// A set of include and exclude pathspecs which decides which files count toward statistics.
+/// A path is counted if it matches any include pathspec (or there are none) and no exclude
+/// pathspec.
+/// ```
+/// # use git_stats::pathspec::PathFilter;
+/// let filter = PathFilter::new(
+///     &["backend".to_string()],
+///     &["*.lock".to_string()],
+/// ).unwrap();
+/// assert!(filter.is_match("backend/src/main.rs"));
+/// assert!(!filter.is_match("backend/Cargo.lock"));
+/// assert!(!filter.is_match("frontend/index.js"));
+/// assert!(PathFilter::default().is_match("frontend/index.js"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    includes: Vec<Pathspec>,
+    excludes: Vec<Pathspec>,
+}
+
+impl PathFilter {
+    /// Creates a filter out of include and exclude pathspecs.
+    /// Include pathspecs using the `exclude` magic are treated as excludes.
+    pub fn new(includes: &[String], excludes: &[String]) -> Result<Self> {
+        let mut filter = Self::default();
+        for spec in includes {
+            let pathspec = Pathspec::new(spec)?;
+            match pathspec.exclude {
+                true => filter.excludes.push(pathspec),
+                false => filter.includes.push(pathspec),
+            };
+        }
+        for spec in excludes {
+            filter.excludes.push(Pathspec::new(spec)?);
+        }
+        return Ok(filter);
+    }
+
+    /// Checks if a path should be counted.
+    pub fn is_match(&self, path: &str) -> bool {
+        let included = self.includes.is_empty() || self.includes.iter().any(|v| v.is_match(path));
+        return included && !self.excludes.iter().any(|v| v.is_match(path));
+    }
+}
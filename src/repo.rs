@@ -6,14 +6,16 @@ use core::fmt;
 use std::collections::BTreeMap;
 use std::{
     ffi::OsString, fs,
-    path::PathBuf, str::FromStr,
+    path::{Component, Path, PathBuf}, str::FromStr,
 };
 
 use crate::objects::{
     GitObjectAttributes, GitObject,
+    blob::BlobSummary,
     commit::CommitObject,
 };
 
+use crate::glob::Glob;
 use crate::macros::ok_or_continue;
 
 // Defines Repo Parsing Error
@@ -38,7 +40,7 @@ pub struct Repo {
     /// This attribute is type `Some([Branches])` if is has.
     pub branches: Option<Box<[OsString]>>,
 
-    cached_object_line_counts: BTreeMap<String, u32>,
+    cached_blob_summaries: BTreeMap<String, BlobSummary>,
 }
 
 impl Repo {
@@ -66,7 +68,7 @@ impl Repo {
                 Repo {
                     dir: git_path,
                     branches: None,
-                    cached_object_line_counts: BTreeMap::new(),
+                    cached_blob_summaries: BTreeMap::new(),
                 });
         } else {
             return Err(anyhow!("Couldn't read repo in path: '{:?}'", git_path));
@@ -76,12 +78,12 @@ impl Repo {
     /// Tried to add item to cache.
     /// Returns Some(v) if the item already exists.
     /// Retunrs None if it doesn't and the item was added.
-    pub fn add_to_cache(&mut self, key: String, value: u32) -> Option<u32> {
-        self.cached_object_line_counts.insert(key, value)
+    pub fn add_to_cache(&mut self, key: String, value: BlobSummary) -> Option<BlobSummary> {
+        self.cached_blob_summaries.insert(key, value)
     }
 
-    pub fn get_from_cache(&self, key: &str) -> Option<&u32> {
-        self.cached_object_line_counts.get(key)
+    pub fn get_from_cache(&self, key: &str) -> Option<&BlobSummary> {
+        self.cached_blob_summaries.get(key)
     }
 
     /// Gets a commit object using the oid
@@ -201,8 +203,17 @@ impl Repo {
     /// let branch = repo.get_branch_oid("main").unwrap(); // Gets the oid of the branch
     /// // A new GitObject can be initialized from this oid
     /// let git_object = GitObject::from_oid(&repo, &branch).unwrap();
+    ///
+    /// // Names can't leave the refs directory
+    /// assert!(repo.get_branch_oid("../../Cargo.toml").is_err());
+    /// assert!(repo.get_branch_oid("/etc/hostname").is_err());
     /// ```
     pub fn get_branch_oid(&self, branch_name: &str) -> Result<String> {
+        let is_inside = Path::new(branch_name)
+            .components()
+            .all(|v| matches!(v, Component::Normal(_)));
+        ensure!(is_inside, "Invalid branch name: '{branch_name}'");
+
         let branch_path = self.dir
             .join("refs")
             .join("heads")
@@ -215,6 +226,99 @@ impl Repo {
         return Ok(out_string.trim().into());
     }
 
+    /// Lists every ref of the repository as `(name, oid)` pairs such as
+    /// `("refs/heads/main", "<oid>")`, from both the loose refs and the `packed-refs` file.
+    /// ```
+    /// # use git_stats::Repo;
+    /// let repo = Repo::from_path(".").unwrap();
+    /// let refs = repo.list_refs().unwrap();
+    /// assert!(refs.iter().all(|(name, _)| name.starts_with("refs/")));
+    /// ```
+    pub fn list_refs(&self) -> Result<Vec<(String, String)>> {
+        let mut refs: BTreeMap<String, String> = BTreeMap::new();
+
+        // Loose refs override packed refs with the same name
+        if let Ok(packed) = fs::read_to_string(self.dir.join("packed-refs")) {
+            for line in packed.lines() {
+                if line.starts_with('#') || line.starts_with('^') {
+                    continue;
+                }
+                if let Some((oid, name)) = line.split_once(' ') {
+                    refs.insert(name.trim().to_string(), oid.to_string());
+                }
+            }
+        }
+
+        let mut directories = vec![self.dir.join("refs")];
+        while let Some(directory) = directories.pop() {
+            for entry in ok_or_continue!(fs::read_dir(&directory)) {
+                let path = ok_or_continue!(entry).path();
+                if path.is_dir() {
+                    directories.push(path);
+                    continue;
+                }
+
+                let name = path
+                    .strip_prefix(&self.dir)?
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                let oid = ok_or_continue!(fs::read_to_string(&path));
+                refs.insert(name, oid.trim().to_string());
+            }
+        }
+
+        return Ok(refs.into_iter().collect());
+    }
+
+    /// Resolves a revision to the refs it names as `(name, oid)` pairs.
+    /// The revision can be a branch or tag name (such as `main`), a full ref name (such as
+    /// `refs/heads/main`) or a glob of full ref names (such as `refs/heads/*`.) Only the refs
+    /// listed by [`Repo::list_refs`] are matched, so a revision can't name any other file.
+    /// ```
+    /// # use git_stats::Repo;
+    /// let repo = Repo::from_path(".").unwrap();
+    /// let branches = repo.resolve_refs("refs/heads/*").unwrap();
+    /// assert!(branches.iter().all(|(name, _)| name.starts_with("refs/heads/")));
+    /// assert!(repo.resolve_refs("/etc/hostname").unwrap().is_empty());
+    /// ```
+    pub fn resolve_refs(&self, revision: &str) -> Result<Vec<(String, String)>> {
+        let refs = self.list_refs()?;
+
+        if revision.contains(['*', '?', '[']) {
+            let glob = Glob::new(revision)?;
+            return Ok(refs.into_iter().filter(|(name, _)| glob.is_match(name)).collect());
+        }
+
+        for name in [
+            revision.to_string(),
+            format!("refs/heads/{revision}"),
+            format!("refs/tags/{revision}"),
+            format!("refs/remotes/{revision}"),
+        ] {
+            if let Some(found) = refs.iter().find(|(v, _)| v == &name) {
+                return Ok(vec![found.to_owned()]);
+            }
+        }
+
+        return Ok(vec![]);
+    }
+
+    /// Gets the full ref name of the branch that is checked out (such as `refs/heads/main`), failing
+    /// if `HEAD` is detached.
+    /// ```
+    /// # use git_stats::Repo;
+    /// let repo = Repo::from_path(".").unwrap();
+    /// let branch = repo.head_branch().unwrap();
+    /// assert!(branch.starts_with("refs/heads/"));
+    /// ```
+    pub fn head_branch(&self) -> Result<String> {
+        let head = fs::read_to_string(self.dir.join("HEAD"))?;
+        return match head.trim().strip_prefix("ref: ") {
+            Some(v) => Ok(v.to_string()),
+            None => Err(anyhow!("HEAD isn't a branch in repo: '{:?}'", self.dir)),
+        };
+    }
+
     /// Gets a commit object by branch name
     /// ```
     /// # use git_stats::Repo;
@@ -0,0 +1,673 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    conventional::ConventionalCommit,
+    diff::{merge_diff, tree_diff, TreeDiff},
+    estimate::{AverageEstimator, Estimator},
+    objects::commit::CommitObject,
+    pathspec::PathFilter,
+    session::{SessionBuilder, WorkSession, DEFAULT_WINDOW},
+    stats::{covered_seconds, DroppedSamples},
+    tickets::TicketMatcher,
+    timestamps::{resolve_time, CommitTime, TimestampAnomaly, TimestampSource},
+    Repo,
+};
+
+/// The statistics of a single commit as it is sent to the front end.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OutputValue {
+    /// The oid of the commit.
+    pub oid: String,
+    /// The label of the repository the commit is in.
+    pub repo: String,
+    /// The message of the commit.
+    pub title: String,
+    /// The seconds spent on the commit.
+    pub delta_t: u64,
+    /// When work on the commit started (RFC 3339.)
+    pub start: String,
+    /// When the commit was made (RFC 3339.)
+    pub end: String,
+    /// See [`TreeDiff::lines_removed`].
+    pub lines_removed: i32,
+    /// See [`TreeDiff::lines_added`].
+    pub lines_added: i32,
+    /// See [`TreeDiff::ignored_lines_removed`].
+    pub ignored_lines_removed: i32,
+    /// See [`TreeDiff::ignored_lines_added`].
+    pub ignored_lines_added: i32,
+    /// See [`TreeDiff::mode_changes`].
+    pub mode_changes: Vec<String>,
+    /// See [`TreeDiff::symlink_retargets`].
+    pub symlink_retargets: Vec<String>,
+    /// See [`TreeDiff::binary_bytes_removed`].
+    pub binary_bytes_removed: u64,
+    /// See [`TreeDiff::binary_bytes_added`].
+    pub binary_bytes_added: u64,
+    /// See [`TreeDiff::lfs_changes`].
+    pub lfs_changes: Vec<String>,
+    /// See [`TreeDiff::lfs_bytes_removed`].
+    pub lfs_bytes_removed: u64,
+    /// See [`TreeDiff::lfs_bytes_added`].
+    pub lfs_bytes_added: u64,
+    /// The branches that contain the commit.
+    pub branches: Vec<String>,
+    /// Which timestamp of the commit was used.
+    pub time_source: TimestampSource,
+    /// The anomalies of the timestamp that was used.
+    pub anomalies: Vec<TimestampAnomaly>,
+    /// The type of the commit if its title is a Conventional Commit (see [`ConventionalCommit`].)
+    #[serde(rename = "type")]
+    pub commit_type: Option<String>,
+    /// The scope of the commit if its title is a Conventional Commit with one.
+    pub scope: Option<String>,
+    /// If the title is a Conventional Commit marked as breaking.
+    pub breaking: bool,
+    /// The tickets the commit references in its message or branches (see [`TicketMatcher`].)
+    pub tickets: Vec<String>,
+}
+
+/// The totals of an author over every session.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AuthorSummary {
+    /// The identity of the author (see [`crate::objects::commit::CommitAuthor::identity`].)
+    pub author: String,
+    /// The amount of sessions the author made the most commits in.
+    pub sessions: usize,
+    /// The amount of commits.
+    pub commits: usize,
+    /// The total seconds spent on the commits, with time spent on several repositories at once
+    /// only counted once.
+    pub seconds: u64,
+    /// The seconds that were projected.
+    pub estimated_seconds: u64,
+    /// The seconds that were measured between commits.
+    pub observed_seconds: u64,
+    /// The seconds that overlapped with other commits of the author (left out of `seconds`.)
+    pub overlapping_seconds: u64,
+    /// The lines removed.
+    pub lines_removed: i64,
+    /// The lines added.
+    pub lines_added: i64,
+}
+
+impl AuthorSummary {
+    /// Totals the commits of sessions by author, with `values` being the output values of each
+    /// session. Sorted by the most seconds first.
+    pub fn from_sessions(sessions: &[WorkSession], values: &[Vec<OutputValue>]) -> Vec<Self> {
+        let mut authors: Vec<Self> = vec![];
+        let mut spans: HashMap<String, Vec<(i64, i64)>> = HashMap::new();
+
+        for (session, values) in sessions.iter().zip(values) {
+            Self::entry(&mut authors, &session.author).sessions += 1;
+
+            for (commit, value) in session.commits.iter().zip(values) {
+                spans
+                    .entry(commit.author.clone())
+                    .or_default()
+                    .push((commit.timestamp - commit.seconds as i64, commit.timestamp));
+
+                let author = Self::entry(&mut authors, &commit.author);
+                author.commits += 1;
+                match commit.estimated {
+                    true => author.estimated_seconds += commit.seconds,
+                    false => author.observed_seconds += commit.seconds,
+                }
+                author.lines_removed += value.lines_removed as i64;
+                author.lines_added += value.lines_added as i64;
+            }
+        }
+
+        for author in authors.iter_mut() {
+            let spans = spans.remove(&author.author).unwrap_or_default();
+            author.seconds = covered_seconds(spans);
+            author.overlapping_seconds =
+                (author.estimated_seconds + author.observed_seconds).saturating_sub(author.seconds);
+        }
+
+        authors.sort_by_key(|v| std::cmp::Reverse(v.seconds));
+        return authors;
+    }
+
+    /// Gets the summary of an author, adding it if it doesn't exist yet.
+    fn entry<'a>(authors: &'a mut Vec<Self>, author: &str) -> &'a mut Self {
+        let index = match authors.iter().position(|v| v.author == author) {
+            Some(v) => v,
+            None => {
+                authors.push(Self {
+                    author: author.to_string(),
+                    ..Default::default()
+                });
+                authors.len() - 1
+            },
+        };
+        return &mut authors[index];
+    }
+}
+
+/// The statistics of a repository.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Report {
+    /// The totals of every author, most seconds first.
+    pub authors: Vec<AuthorSummary>,
+    /// The work sessions, newest first.
+    pub sessions: Vec<WorkSession>,
+    /// The statistics of the commits of each session, in the same order as the sessions.
+    pub values: Vec<Vec<OutputValue>>,
+    /// The commits left out of the rate statistics used to project session starts.
+    pub dropped_samples: DroppedSamples,
+    /// The oid of the last commit walked when the walk stopped at the max count, which the next
+    /// page continues after (see [`StatsBuilder::cursor`].) `None` if there are no more commits.
+    /// Sessions are sorted by when they end, so the last commit of the output isn't always the
+    /// last commit walked.
+    pub cursor: Option<String>,
+}
+
+impl Report {
+    /// Merges the reports of several repositories into one timeline, newest session first.
+    /// The totals of authors don't count time spent in several repositories at once twice.
+    pub fn merge(reports: Vec<Report>) -> Self {
+        // The cursor of a single repository is an oid that can't be shared with other repositories
+        let cursor = match reports.as_slice() {
+            [report] => report.cursor.clone(),
+            _ => None,
+        };
+        let mut dropped_samples = DroppedSamples::default();
+        for report in &reports {
+            dropped_samples += report.dropped_samples;
+        }
+
+        let mut entries = reports
+            .into_iter()
+            .flat_map(|v| v.sessions.into_iter().zip(v.values))
+            .collect::<Vec<(WorkSession, Vec<OutputValue>)>>();
+        entries.sort_by_key(|(session, _)| std::cmp::Reverse(session.end));
+
+        let (sessions, values): (Vec<WorkSession>, Vec<Vec<OutputValue>>) = entries.into_iter().unzip();
+        return Self {
+            authors: AuthorSummary::from_sessions(&sessions, &values),
+            sessions,
+            values,
+            dropped_samples,
+            cursor,
+        };
+    }
+}
+
+/// The error of a walk that never reached its cursor (see [`StatsBuilder::cursor`].)
+#[derive(Debug, Clone)]
+pub struct UnknownCursor(pub String);
+
+impl fmt::Display for UnknownCursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "Unknown cursor: '{}'", self.0);
+    }
+}
+
+impl std::error::Error for UnknownCursor {}
+
+/// The error of a revision that doesn't match any ref (see [`StatsBuilder::revisions`].)
+#[derive(Debug, Clone)]
+pub struct UnknownRevision(pub String);
+
+impl fmt::Display for UnknownRevision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "No refs match revision: '{}'", self.0);
+    }
+}
+
+impl std::error::Error for UnknownRevision {}
+
+/// Gets the name of a directory to label a repository with, even for paths such as `.`.
+fn directory_name(directory: &Path) -> String {
+    let directory = std::fs::canonicalize(directory).unwrap_or(directory.to_path_buf());
+    return match directory.file_name() {
+        Some(v) => v.to_string_lossy().to_string(),
+        None => directory.to_string_lossy().to_string(),
+    };
+}
+
+/// Shortens a ref name the way git shows it (such as `refs/heads/main` to `main`.)
+fn short_ref_name(name: &str) -> String {
+    for prefix in ["refs/heads/", "refs/tags/", "refs/remotes/"] {
+        if let Some(v) = name.strip_prefix(prefix) {
+            return v.to_string();
+        }
+    }
+    return name.to_string();
+}
+
+/// Walks a repository and creates a [`Report`] of it.
+/// ```no_run
+/// # use git_stats::estimate::MedianEstimator;
+/// # use git_stats::report::StatsBuilder;
+/// let report = StatsBuilder::new(".")
+///     .revision("main")
+///     .include(vec!["src".into()])
+///     .window(3 * 3600)
+///     .estimator(Box::new(MedianEstimator::default()))
+///     .build()
+///     .unwrap();
+///
+/// for session in report.sessions {
+///     println!("{} worked for {}s", session.author, session.duration());
+/// }
+/// ```
+pub struct StatsBuilder {
+    directory: PathBuf,
+    label: Option<String>,
+    revisions: Vec<String>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    email: Option<String>,
+    committer: Option<String>,
+    since: Option<i64>,
+    until: Option<i64>,
+    max_count: Option<usize>,
+    cursor: Option<String>,
+    per_author: bool,
+    window: u64,
+    author_windows: Vec<(String, u64)>,
+    estimator: Box<dyn Estimator>,
+    fallback_estimator: Option<Box<dyn Estimator>>,
+    timestamp_source: TimestampSource,
+    binary_weight: f32,
+    lfs_weight: Option<f32>,
+    ticket_patterns: Vec<String>,
+}
+
+impl StatsBuilder {
+    /// Creates a builder for the repository in `directory`, walking the `main` branch.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        return Self {
+            directory: directory.into(),
+            label: None,
+            revisions: vec!["main".to_string()],
+            include: vec![],
+            exclude: vec![],
+            email: None,
+            committer: None,
+            since: None,
+            until: None,
+            max_count: None,
+            cursor: None,
+            per_author: false,
+            window: DEFAULT_WINDOW,
+            author_windows: vec![],
+            estimator: Box::new(AverageEstimator::default()),
+            fallback_estimator: None,
+            timestamp_source: TimestampSource::Committer,
+            binary_weight: 1.0,
+            lfs_weight: None,
+            ticket_patterns: vec![],
+        };
+    }
+
+    /// Sets the label of the repository in the report, the name of its directory if `None`.
+    pub fn label(mut self, label: Option<String>) -> Self {
+        self.label = label;
+        return self;
+    }
+
+    /// Sets the branch the walk starts from (see [`Repo::resolve_refs`].)
+    pub fn revision(mut self, revision: &str) -> Self {
+        self.revisions = vec![revision.to_string()];
+        return self;
+    }
+
+    /// Sets the branches the walk starts from, commits reachable from several of them are only
+    /// counted once.
+    pub fn revisions(mut self, revisions: Vec<String>) -> Self {
+        self.revisions = revisions;
+        return self;
+    }
+
+    /// Sets the pathspecs of the files that are counted (everything if empty.)
+    pub fn include(mut self, include: Vec<String>) -> Self {
+        self.include = include;
+        return self;
+    }
+
+    /// Sets the pathspecs of the files that aren't counted.
+    pub fn exclude(mut self, exclude: Vec<String>) -> Self {
+        self.exclude = exclude;
+        return self;
+    }
+
+    /// Only walks commits written by an author with this email.
+    pub fn email(mut self, email: Option<String>) -> Self {
+        self.email = email;
+        return self;
+    }
+
+    /// Only walks commits written by an author with this name.
+    pub fn committer(mut self, committer: Option<String>) -> Self {
+        self.committer = committer;
+        return self;
+    }
+
+    /// Only counts commits made at or after this unix timestamp.
+    /// The walk stops at the first commit before it.
+    pub fn since(mut self, since: Option<i64>) -> Self {
+        self.since = since;
+        return self;
+    }
+
+    /// Only counts commits made at or before this unix timestamp.
+    pub fn until(mut self, until: Option<i64>) -> Self {
+        self.until = until;
+        return self;
+    }
+
+    /// Stops the walk after counting this many commits.
+    pub fn max_count(mut self, max_count: Option<usize>) -> Self {
+        self.max_count = max_count;
+        return self;
+    }
+
+    /// Only counts the commits walked after this commit oid, failing with [`UnknownCursor`] if the
+    /// walk never reaches it. Used for paging with the [`Report::cursor`] of the previous page.
+    pub fn cursor(mut self, cursor: Option<String>) -> Self {
+        self.cursor = cursor;
+        return self;
+    }
+
+    /// Sets the seconds between two commits before a new session starts.
+    pub fn window(mut self, window: u64) -> Self {
+        self.window = window;
+        return self;
+    }
+
+    /// Creates separate sessions for every author (always the case when filtering by email or
+    /// committer.)
+    pub fn per_author(mut self, per_author: bool) -> Self {
+        self.per_author = per_author;
+        return self;
+    }
+
+    /// Overrides the window for an author by email or name, later overrides win.
+    pub fn author_window(mut self, author: &str, window: u64) -> Self {
+        self.author_windows.push((author.to_string(), window));
+        return self;
+    }
+
+    /// Sets how the start of sessions is projected.
+    pub fn estimator(mut self, estimator: Box<dyn Estimator>) -> Self {
+        self.estimator = estimator;
+        return self;
+    }
+
+    /// Sets how the start of sessions is projected when the estimator has nothing to project from
+    /// (see [`SessionBuilder::fallback`]), the default of the session builder if `None`.
+    pub fn fallback_estimator(mut self, fallback_estimator: Option<Box<dyn Estimator>>) -> Self {
+        self.fallback_estimator = fallback_estimator;
+        return self;
+    }
+
+    /// Sets which timestamp of a commit is used.
+    pub fn timestamp_source(mut self, timestamp_source: TimestampSource) -> Self {
+        self.timestamp_source = timestamp_source;
+        return self;
+    }
+
+    /// Counts binary files as `binary_weight` lines per KiB.
+    pub fn binary_weight(mut self, binary_weight: f32) -> Self {
+        self.binary_weight = binary_weight;
+        return self;
+    }
+
+    /// Counts Git LFS objects as `lfs_weight` lines per KiB, the binary weight if `None`.
+    pub fn lfs_weight(mut self, lfs_weight: Option<f32>) -> Self {
+        self.lfs_weight = lfs_weight;
+        return self;
+    }
+
+    /// Sets the regexes that find tickets in commit messages and branch names (see
+    /// [`TicketMatcher`]), the defaults if empty.
+    pub fn ticket_patterns(mut self, ticket_patterns: Vec<String>) -> Self {
+        self.ticket_patterns = ticket_patterns;
+        return self;
+    }
+
+    /// Gets the label of the repository in the report (see [`StatsBuilder::label`].)
+    pub fn repo_label(&self) -> String {
+        return match &self.label {
+            Some(v) => v.to_owned(),
+            None => directory_name(&self.directory),
+        };
+    }
+
+    /// Walks the repository and creates the report.
+    pub fn build(self) -> Result<Report> {
+        let mut repo = Repo::from_pathbuf(&self.directory)?;
+        let label = self.repo_label();
+
+        let lfs_weight = self.lfs_weight.unwrap_or(self.binary_weight);
+        let filter = PathFilter::new(&self.include, &self.exclude)?;
+        let tickets = TicketMatcher::new(&self.ticket_patterns)?;
+        let now = Utc::now().timestamp();
+
+        // Filtering by author leaves gaps in the history, so only their own commits are
+        // measured from each other
+        let per_author = self.per_author || self.email.is_some() || self.committer.is_some();
+        let mut sessions = SessionBuilder::new(self.estimator)
+            .window(self.window)
+            .per_author(per_author);
+        for (author, window) in &self.author_windows {
+            sessions = sessions.author_window(author, *window);
+        }
+        if let Some(fallback) = self.fallback_estimator {
+            sessions = sessions.fallback(fallback);
+        }
+        // The diff, time and tickets (from the message and the branches, see below) of every
+        // counted commit
+        let mut walked: HashMap<String, (TreeDiff, CommitTime, Vec<String>)> = HashMap::new();
+
+        // The commits waiting to be walked with the branches that contain every commit seen, and
+        // the branches whose first parent line every commit is on. Commits of a merged branch are
+        // only on the first parent line of their own branch, so that is where tickets come from.
+        let mut pending: Vec<CommitObject> = vec![];
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut branches: HashMap<String, BTreeSet<String>> = HashMap::new();
+        let mut lines: HashMap<String, BTreeSet<String>> = HashMap::new();
+
+        for revision in &self.revisions {
+            let refs = repo.resolve_refs(revision)?;
+            if refs.is_empty() {
+                return Err(UnknownRevision(revision.to_owned()).into());
+            }
+
+            for (name, oid) in refs {
+                branches.entry(oid.clone()).or_default().insert(short_ref_name(&name));
+                lines.entry(oid.clone()).or_default().insert(short_ref_name(&name));
+                if visited.insert(oid.clone()) {
+                    match CommitObject::from_oid(&repo, &oid) {
+                        Ok(v) => pending.push(v),
+                        Err(e) => log::warn!("Skipping ref '{name}' that isn't a commit: {e}"),
+                    }
+                }
+            }
+        }
+
+        // Commits before the cursor were on a previous page
+        let mut is_skipping = self.cursor.is_some();
+        let mut last_walked: Option<String> = None;
+        let mut is_cut_short = false;
+
+        // Always walks the newest commit of any branch next
+        while let Some(index) = pending
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, v)| v.committer.timestamp)
+            .map(|(i, _)| i) {
+
+            if self.max_count.is_some_and(|v| walked.len() >= v) {
+                is_cut_short = true;
+                break;
+            }
+
+            let branch = pending.swap_remove(index);
+
+            let parents = match branch.parents
+                .iter()
+                .map(|oid| CommitObject::from_oid(&repo, oid))
+                .collect::<Result<Vec<CommitObject>>>() {
+                Ok(v) => v,
+                Err(_) => {
+                    log::warn!("Can't find the parents of commit: '{}'", branch.oid);
+                    continue;
+                },
+            };
+
+            let commit_branches = branches.get(&branch.oid).cloned().unwrap_or_default();
+            for parent in &parents {
+                branches.entry(parent.oid.clone()).or_default().extend(commit_branches.iter().cloned());
+                if visited.insert(parent.oid.clone()) {
+                    pending.push(parent.clone());
+                }
+            }
+
+            let commit_lines = lines.remove(&branch.oid).unwrap_or_default();
+            if let Some(parent) = parents.first() {
+                lines.entry(parent.oid.clone()).or_default().extend(commit_lines.iter().cloned());
+            }
+
+            if is_skipping {
+                is_skipping = self.cursor.as_ref() != Some(&branch.oid);
+                continue;
+            }
+            last_walked = Some(branch.oid.clone());
+
+            let time = resolve_time(&branch, parents.first(), self.timestamp_source, now);
+            if DateTime::from_timestamp(time.timestamp, 0).is_none() {
+                eprintln!("Warning: skipped commit '{}' with a timestamp out of range: {}", branch.oid, time.timestamp);
+                continue;
+            }
+            if !time.anomalies.is_empty() {
+                log::warn!("Commit '{}' has timestamp anomalies: {:?}", branch.oid, time.anomalies);
+            }
+
+            // The walk is in committer time order so everything after this is older, while the
+            // time used (such as the author time) can be out of order and is only filtered
+            if self.since.is_some_and(|v| (branch.committer.timestamp as i64) < v) {
+                break;
+            }
+
+            // Commits of other authors are skipped instead of ending the walk, matching the author
+            // that sessions belong to rather than whoever applied the commit
+            let is_counted = self.since.is_none_or(|v| time.timestamp >= v) &&
+                self.until.is_none_or(|v| time.timestamp <= v) &&
+                self.email.as_ref().is_none_or(|v| branch.author.email.as_ref() == Some(v)) &&
+                self.committer.as_ref().is_none_or(|v| &branch.author.name == v);
+
+            if is_counted {
+                let tree = branch.get_tree(&repo)?.recurs_create_tree_line_count(&mut repo, "", &filter);
+
+                let mut parent_trees = vec![];
+                for parent in &parents {
+                    parent_trees.push(parent.get_tree(&repo)?.recurs_create_tree_line_count(&mut repo, "", &filter));
+                }
+
+                // The root commit is compared to an empty tree and merges only count what they
+                // changed themselves
+                let difference = match parent_trees.len() {
+                    0 => tree_diff(tree, HashMap::new()),
+                    1 => tree_diff(tree, parent_trees.pop().unwrap()),
+                    _ => merge_diff(tree, parent_trees),
+                };
+
+                sessions.push(
+                    &branch,
+                    &time,
+                    difference.weighted_lines_removed(self.binary_weight, lfs_weight),
+                    difference.weighted_lines_added(self.binary_weight, lfs_weight),
+                    commit_branches.into_iter().collect(),
+                );
+                let commit_tickets = tickets.find(
+                    &format!("{}\n{}", branch.message, branch.body),
+                    &commit_lines.into_iter().collect::<Vec<String>>(),
+                );
+                walked.insert(branch.oid.clone(), (difference, time, commit_tickets));
+            }
+        }
+
+        if is_skipping {
+            return Err(UnknownCursor(self.cursor.unwrap_or_default()).into());
+        }
+        let cursor = match is_cut_short {
+            true => last_walked.or(self.cursor.clone()),
+            false => None,
+        };
+
+        let (mut sessions, dropped_samples) = sessions.build();
+        if dropped_samples.projected_without_data {
+            eprintln!("Warning: no commits in '{label}' to project session starts from, used the fallback estimator instead.");
+        }
+        for session in sessions.iter_mut() {
+            session.repo = label.clone();
+        }
+
+        // Commits with timestamps out of range were skipped, but a projected start can still be
+        let rfc3339 = |oid: &str, timestamp: Option<i64>| timestamp
+            .and_then(|v| DateTime::from_timestamp(v, 0))
+            .map(|v| v.to_rfc3339())
+            .ok_or_else(|| anyhow!("Time of commit '{oid}' is out of range"));
+
+        let values = sessions
+            .iter()
+            .map(|session| {
+                return session.commits.iter().map(|commit| {
+                    // Every commit in a session was walked
+                    let (diff, time, commit_tickets) = walked.remove(&commit.oid).unwrap();
+                    let conventional = ConventionalCommit::parse(&commit.title);
+                    let start = i64::try_from(commit.seconds)
+                        .ok()
+                        .and_then(|v| commit.timestamp.checked_sub(v));
+                    return Ok(OutputValue {
+                        oid: commit.oid.clone(),
+                        repo: label.clone(),
+                        title: commit.title.clone(),
+                        delta_t: commit.seconds,
+                        end: rfc3339(&commit.oid, Some(commit.timestamp))?,
+                        start: rfc3339(&commit.oid, start)?,
+                        lines_removed: diff.lines_removed,
+                        lines_added: diff.lines_added,
+                        ignored_lines_removed: diff.ignored_lines_removed,
+                        ignored_lines_added: diff.ignored_lines_added,
+                        mode_changes: diff.mode_changes,
+                        symlink_retargets: diff.symlink_retargets,
+                        binary_bytes_removed: diff.binary_bytes_removed,
+                        binary_bytes_added: diff.binary_bytes_added,
+                        lfs_changes: diff.lfs_changes,
+                        lfs_bytes_removed: diff.lfs_bytes_removed,
+                        lfs_bytes_added: diff.lfs_bytes_added,
+                        branches: commit.branches.clone(),
+                        time_source: time.source,
+                        anomalies: time.anomalies,
+                        commit_type: conventional.as_ref().map(|v| v.kind.clone()),
+                        scope: conventional.as_ref().and_then(|v| v.scope.clone()),
+                        breaking: conventional.is_some_and(|v| v.breaking),
+                        tickets: commit_tickets,
+                    });
+                })
+                .collect::<Result<Vec<OutputValue>>>();
+            })
+            .collect::<Result<Vec<Vec<OutputValue>>>>()?;
+
+        return Ok(Report {
+            authors: AuthorSummary::from_sessions(&sessions, &values),
+            sessions,
+            values,
+            dropped_samples,
+            cursor,
+        });
+    }
+}
@@ -8,7 +8,17 @@ use regex::Regex;
 use anyhow::{anyhow, Context, Result};
 use httparse;
 
-use crate::{cli::cli, OutputValue};
+use chrono::{DateTime, Local, NaiveDate};
+use git_stats::{
+    calendar::ContributionCalendar,
+    conventional::ConventionalSummary,
+    heatmap::Heatmap,
+    report::{OutputValue, Report, StatsBuilder, UnknownCursor, UnknownRevision},
+    summary::{Period, PeriodSummary},
+    tickets::TicketSummary,
+};
+
+use crate::cli::cli;
 
 fn get_path(stream: &mut TcpStream) -> Result<String> {
 
@@ -50,9 +60,9 @@ fn get_path(stream: &mut TcpStream) -> Result<String> {
 /// Function for ensuring that a given path is inside of the configured server directory
 /// This gets the absolute path for both the target and expected directory and checks if the target
 /// starts with the expected directory.
-fn sanitize_path(dir: &str, args: &cli::CliArgs) -> Result<()> {
+fn sanitize_path(dir: &str, server_directory: &str) -> Result<()> {
     let canonical_target = fs::canonicalize(dir).map_err(anyhow::Error::from)?;
-    let canonical_src = fs::canonicalize(&args.server_directory).map_err(anyhow::Error::from)?;
+    let canonical_src = fs::canonicalize(server_directory).map_err(anyhow::Error::from)?;
 
     if canonical_target.starts_with(canonical_src) {
         return Ok(());
@@ -61,62 +71,387 @@ fn sanitize_path(dir: &str, args: &cli::CliArgs) -> Result<()> {
     }
 }
 
+/// Decodes the `%xx` escapes and `+` spaces of a query string component.
+fn percent_decode(in_str: &str) -> String {
+    let bytes = in_str.as_bytes();
+    let mut out: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = String::from_utf8_lossy(&bytes[i + 1..i + 3]).to_string();
+                match u8::from_str_radix(&hex, 16) {
+                    Ok(v) => {
+                        out.push(v);
+                        i += 2;
+                    },
+                    Err(_) => out.push(b'%'),
+                }
+            },
+            v => out.push(v),
+        }
+        i += 1;
+    }
+
+    return String::from_utf8_lossy(&out).to_string();
+}
+
+/// Splits a query string into its decoded key value pairs.
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    return query
+        .split('&')
+        .filter(|v| !v.is_empty())
+        .map(|v| match v.split_once('=') {
+            Some((key, value)) => (percent_decode(key), percent_decode(value)),
+            None => (percent_decode(v), String::new()),
+        })
+        .collect();
+}
+
+/// Overrides the stats builder with the query parameters of a data request.
+fn apply_query(mut builder: StatsBuilder, query: &[(String, String)]) -> Result<StatsBuilder> {
+    let values = |name: &str| query
+        .iter()
+        .filter(|(key, _)| key == name)
+        .map(|(_, value)| value.to_owned())
+        .collect::<Vec<String>>();
+
+    let branch = values("branch");
+    if !branch.is_empty() {
+        builder = builder.revisions(branch);
+    }
+
+    let include = values("include");
+    if !include.is_empty() {
+        builder = builder.include(include);
+    }
+
+    let exclude = values("exclude");
+    if !exclude.is_empty() {
+        builder = builder.exclude(exclude);
+    }
+
+    if let Some(window) = values("window").last() {
+        builder = builder.window(cli::parse_duration(window)?);
+    }
+
+    if let Some(since) = values("since").last() {
+        builder = builder.since(Some(cli::parse_since(since)?));
+    }
+
+    if let Some(until) = values("until").last() {
+        builder = builder.until(Some(cli::parse_until(until)?));
+    }
+
+    if let Some(limit) = values("limit").last() {
+        builder = builder.max_count(Some(limit.parse().map_err(|_| anyhow!("Can't parse limit '{limit}'"))?));
+    }
+
+    if let Some(per_author) = values("per_author").last() {
+        builder = builder.per_author(per_author != "false");
+    }
+
+    for author_window in values("author_window") {
+        let (author, window) = cli::parse_author_window(&author_window)?;
+        builder = builder.author_window(&author, window);
+    }
+
+    for (key, _) in query {
+        match key.as_str() {
+            "branch" | "include" | "exclude" | "window" | "author_window" | "since" | "until" | "limit" | "cursor" | "per_author" | "period" => (),
+            _ => log::warn!("Ignoring unknown query parameter: '{key}'"),
+        }
+    }
+
+    return Ok(builder);
+}
+
+/// The path of the endpoint with the totals of every author.
+const AUTHORS_URI: &str = "/api/authors";
+
+/// The path of the endpoint with the totals of every day, week or month.
+const SUMMARY_URI: &str = "/api/summary";
+
+/// The path of the endpoint with the heatmap of the hours of the week.
+const HEATMAP_URI: &str = "/api/heatmap";
+
+/// The path of the endpoint with the contribution calendar of the last year.
+const CALENDAR_URI: &str = "/api/calendar";
+
+/// The path of the endpoint with the time spent on every Conventional Commit type and scope.
+const TYPES_URI: &str = "/api/types";
+
+/// The path of the endpoint with the time spent on every ticket.
+const TICKETS_URI: &str = "/api/tickets";
+
+/// The path of the endpoint with the commits left out of the rates used to project session starts.
+const DROPPED_URI: &str = "/api/dropped";
+
 enum OutputType {
     File(String),
     GetData,
+    Authors,
+    Summary,
+    Heatmap,
+    Calendar,
+    Types,
+    Tickets,
+    Dropped,
+}
+
+/// Gets the period of the summary endpoint from the `period` query parameter, weeks by default.
+fn summary_period(query: &[(String, String)]) -> Result<Period> {
+    let period = query.iter().rev().find(|(key, _)| key == "period").map(|(_, v)| v.as_str());
+    return match period {
+        Some("day") => Ok(Period::Day),
+        Some("week") | None => Ok(Period::Week),
+        Some("month") => Ok(Period::Month),
+        Some(v) => Err(anyhow!("Unknown period '{v}', expected day, week or month")),
+    };
+}
+
+/// Gets the last day of the contribution calendar, the day of the `until` query parameter or today.
+fn calendar_end(query: &[(String, String)]) -> NaiveDate {
+    let end = query
+        .iter()
+        .rev()
+        .find(|(key, _)| key == "until")
+        .and_then(|(_, v)| cli::parse_until(v).ok())
+        .and_then(|v| DateTime::from_timestamp(v, 0))
+        .map(|v| v.with_timezone(&Local))
+        .unwrap_or(Local::now());
+    return end.date_naive();
 }
 
-pub fn handle_connection(mut stream: TcpStream, path: &str, args: &cli::CliArgs) {
+/// The header with the cursor of the next page, left out when there are no more commits.
+const CURSOR_HEADER: &str = "Next-Cursor";
 
-    let out_path = get_path(&mut stream).unwrap_or("/404".to_string());
+/// Gets the cursor of every repo from the `cursor` query parameter.
+/// With one repo the cursor is an oid (see [`StatsBuilder::cursor`]) and with several repos it is
+/// `index:oid` for every repo with more commits separated by commas, the others getting `None`.
+/// Repos are keyed by their index since several repos can have the same label.
+fn parse_cursor(cursor: &str, repo_count: usize) -> Result<Vec<Option<String>>> {
+    if repo_count == 1 {
+        return Ok(vec![Some(cursor.to_string())]);
+    }
+
+    let mut cursors = vec![None; repo_count];
+    for part in cursor.split(',').filter(|v| !v.is_empty()) {
+        let (index, oid) = part
+            .split_once(':')
+            .ok_or(anyhow!("Cursor '{part}' must look like 'index:oid'"))?;
+        let index = index
+            .parse::<usize>()
+            .ok()
+            .filter(|v| *v < repo_count)
+            .ok_or(anyhow!("Unknown repository in cursor: '{index}'"))?;
+        cursors[index] = Some(oid.to_string());
+    }
+    return Ok(cursors);
+}
+
+/// Gets the cursor of the next page from the reports of every repo (see [`parse_cursor`]), `None`
+/// if no repo has more commits.
+fn next_cursor(reports: &[Report]) -> Option<String> {
+    if let [report] = reports {
+        return report.cursor.clone();
+    }
+
+    let cursors = reports
+        .iter()
+        .enumerate()
+        .filter_map(|(i, report)| report.cursor.as_ref().map(|v| format!("{i}:{v}")))
+        .collect::<Vec<String>>();
+    return match cursors.is_empty() {
+        true => None,
+        false => Some(cursors.join(",")),
+    };
+}
+
+/// Builds the reports of every repo with the query applied, merges them and serializes the part
+/// that was requested along with the extra headers of the response.
+fn report_response(builders: Result<Vec<StatsBuilder>>, query: &[(String, String)], output_type: &OutputType) -> (&'static str, Vec<u8>, Vec<String>) {
+    let builders = match builders {
+        Ok(v) => v,
+        Err(v) => return ("HTTP/1.1 500 INTERNAL SERVER ERROR", format!("{v}").into(), vec![]),
+    };
+    let period = match summary_period(query) {
+        Ok(v) => v,
+        Err(v) => return ("HTTP/1.1 400 BAD REQUEST", format!("{v}").into(), vec![]),
+    };
+
+    // Every repo is paged on its own, so the cursor has one for each repo
+    let cursors = match query.iter().rev().find(|(key, _)| key == "cursor") {
+        Some((_, cursor)) => match parse_cursor(cursor, builders.len()) {
+            Ok(v) => Some(v),
+            Err(v) => return ("HTTP/1.1 400 BAD REQUEST", format!("{v}").into(), vec![]),
+        },
+        None => None,
+    };
+
+    let builders = match builders.into_iter().map(|v| apply_query(v, query)).collect::<Result<Vec<StatsBuilder>>>() {
+        Ok(v) => v,
+        Err(v) => return ("HTTP/1.1 400 BAD REQUEST", format!("{v}").into(), vec![]),
+    };
+
+    let mut reports: Vec<Report> = vec![];
+    for (i, builder) in builders.into_iter().enumerate() {
+        let builder = match &cursors {
+            // Repos left out of the cursor have no more commits
+            Some(cursors) => match &cursors[i] {
+                Some(v) => builder.cursor(Some(v.to_owned())),
+                None => {
+                    reports.push(Report::default());
+                    continue;
+                },
+            },
+            None => builder,
+        };
+        match builder.build() {
+            Ok(v) => reports.push(v),
+            // Cursors and revisions that don't exist come from the request, not the repository
+            Err(v) if v.is::<UnknownCursor>() || v.is::<UnknownRevision>() => {
+                return ("HTTP/1.1 400 BAD REQUEST", format!("{v}").into(), vec![]);
+            },
+            Err(v) => return ("HTTP/1.1 500 INTERNAL SERVER ERROR", format!("{v}").into(), vec![]),
+        }
+    }
+
+    let headers = next_cursor(&reports)
+        .iter()
+        .map(|v| format!("{CURSOR_HEADER}: {v}"))
+        .collect::<Vec<String>>();
+    let report = Report::merge(reports);
+
+    let data = match output_type {
+        OutputType::Authors => serde_json::to_vec(&report.authors),
+        OutputType::Heatmap => serde_json::to_vec(&Heatmap::from_sessions(&report.sessions)),
+        OutputType::Types => serde_json::to_vec(&ConventionalSummary::from_sessions(&report.sessions, &report.values)),
+        OutputType::Tickets => serde_json::to_vec(&TicketSummary::from_sessions(&report.sessions, &report.values)),
+        OutputType::Dropped => serde_json::to_vec(&report.dropped_samples),
+        OutputType::Calendar => serde_json::to_vec(&ContributionCalendar::from_sessions(&report.sessions, calendar_end(query))),
+        OutputType::Summary => serde_json::to_vec(&PeriodSummary::from_sessions(&report.sessions, &report.values, period, &Local)),
+        _ => serde_json::to_vec(&report.values.into_iter().flatten().collect::<Vec<OutputValue>>()),
+    };
+    return ("HTTP/1.1 200 OK", data.unwrap(), headers);
+}
+
+/// Responds to a request, `stats` creating the builders of every repo for data requests.
+pub fn handle_connection(mut stream: TcpStream, path: &str, server_uri: &str, stats: &dyn Fn() -> Result<Vec<StatsBuilder>>) {
+
+    let request_path = get_path(&mut stream).unwrap_or("/404".to_string());
+    let (out_path, query) = match request_path.split_once('?') {
+        Some((out_path, query)) => (out_path.to_string(), parse_query(query)),
+        None => (request_path, vec![]),
+    };
     let output_value: OutputType;
 
-    if out_path == args.server_uri {
+    if out_path == server_uri {
         output_value = OutputType::GetData;
+    } else if out_path == AUTHORS_URI {
+        output_value = OutputType::Authors;
+    } else if out_path == SUMMARY_URI {
+        output_value = OutputType::Summary;
+    } else if out_path == HEATMAP_URI {
+        output_value = OutputType::Heatmap;
+    } else if out_path == CALENDAR_URI {
+        output_value = OutputType::Calendar;
+    } else if out_path == TYPES_URI {
+        output_value = OutputType::Types;
+    } else if out_path == TICKETS_URI {
+        output_value = OutputType::Tickets;
+    } else if out_path == DROPPED_URI {
+        output_value = OutputType::Dropped;
     } else if out_path == "/" {
         output_value = OutputType::File("/index.html".to_string());
     } else {
         output_value = OutputType::File(out_path.clone());
     }
 
-    let (status_line, contents) = match output_value {
-        OutputType::GetData => {
-            match super::get_data(args) {
-                Ok(data_content) => {
-                    let mut flattened_content: Vec<OutputValue> = vec![];
-                    for value in data_content {
-                        for entry in value {
-                            flattened_content.push(entry);
-                        }
-                    }
-                    let data = serde_json::to_vec(&flattened_content).unwrap();
-                    ("HTTP/1.1 200 OK", data)
-                },
-                Err(v) => {
-                    ("HTTP/1.1 500 INTERNAL SERVER ERROR", format!("{v}").into())
-                },
-            }
-        },
+    let (status_line, contents, headers) = match output_value {
+        OutputType::GetData
+            | OutputType::Authors
+            | OutputType::Summary
+            | OutputType::Heatmap
+            | OutputType::Calendar
+            | OutputType::Types
+            | OutputType::Tickets
+            | OutputType::Dropped => report_response(stats(), &query, &output_value),
         OutputType::File(filename) => {
 
             let file_path = format!("{}/{}", path, filename.trim_start_matches("/"));
 
-            let cleaned_path = sanitize_path(&file_path, args);
+            let cleaned_path = sanitize_path(&file_path, path);
             if let Err(_) = cleaned_path {
                 let out = b"404, not found!".to_vec();
-                ("HTTP/1.1 404 NOT FOUND", out)
+                ("HTTP/1.1 404 NOT FOUND", out, vec![])
             } else {
                 // file not existing should be handled in `sanitize_path()` func
-                ("HTTP/1.1 200 OK", fs::read(file_path).unwrap())
+                ("HTTP/1.1 200 OK", fs::read(file_path).unwrap(), vec![])
             }
         },
     };
     let length = contents.len();
 
-    let mut response: Vec<u8> = format!("{status_line}\r\nContent-Length: {length}\r\n\r\n")
+    let headers = headers.iter().map(|v| format!("{v}\r\n")).collect::<String>();
+    let mut response: Vec<u8> = format!("{status_line}\r\nContent-Length: {length}\r\n{headers}\r\n")
         .into_bytes()
         .to_owned();
     response.extend(contents.iter());
 
     stream.write_all(&response).unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_query_components() {
+        assert_eq!(percent_decode("main"), "main");
+        assert_eq!(percent_decode("feature%2Flogin"), "feature/login");
+        assert_eq!(percent_decode("3+days+ago"), "3 days ago");
+        assert_eq!(percent_decode("a%3Aoid%2Cb%3Aoid"), "a:oid,b:oid");
+        assert_eq!(percent_decode("%C3%A9"), "é");
+    }
+
+    #[test]
+    fn decode_malformed_query_components() {
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%4"), "%4");
+        assert_eq!(percent_decode("%zz"), "%zz");
+        assert_eq!(percent_decode("%%41"), "%A");
+        assert_eq!(percent_decode("%FF"), "\u{FFFD}");
+    }
+
+    #[test]
+    fn cursors() {
+        assert_eq!(parse_cursor("1111", 1).unwrap(), vec![Some("1111".to_string())]);
+        assert_eq!(parse_cursor("1:2222", 3).unwrap(), vec![None, Some("2222".to_string()), None]);
+        assert_eq!(parse_cursor("", 2).unwrap(), vec![None, None]);
+    }
+
+    #[test]
+    fn malformed_cursors() {
+        assert!(parse_cursor("2222", 2).is_err());
+        assert!(parse_cursor("2:2222", 2).is_err());
+        assert!(parse_cursor("api:2222", 2).is_err());
+        assert!(parse_cursor("-1:2222", 2).is_err());
+        assert!(parse_cursor("99999999999999999999999:2222", 2).is_err());
+    }
+
+    #[test]
+    fn cursor_of_repos_with_the_same_name() {
+        // Both would be labeled `api`, as with `-d a/api -d b/api`
+        let reports = vec![
+            Report { cursor: Some("1111".to_string()), ..Default::default() },
+            Report { cursor: Some("2222".to_string()), ..Default::default() },
+        ];
+
+        let cursor = next_cursor(&reports).unwrap();
+        assert_eq!(cursor, "0:1111,1:2222");
+        assert_eq!(parse_cursor(&cursor, 2).unwrap(), vec![Some("1111".to_string()), Some("2222".to_string())]);
+    }
+}
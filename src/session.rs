@@ -0,0 +1,423 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    estimate::{Estimator, EstimatorSample, FixedEstimator},
+    objects::commit::{CommitAuthor, CommitObject},
+    stats::DroppedSamples,
+    timestamps::CommitTime,
+};
+
+/// The default longest gap between two commits of the same work session (5 hours.)
+pub const DEFAULT_WINDOW: u64 = 3600 * 5;
+
+/// The default seconds projected for the start of a session when the estimator has nothing to
+/// project from.
+pub const DEFAULT_FALLBACK: u64 = 1800;
+
+/// A commit that is part of a [`WorkSession`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SessionCommit {
+    /// The oid of the commit.
+    pub oid: String,
+    /// The first line of the commit message.
+    pub title: String,
+    /// The identity of the author (see [`CommitAuthor::identity`]), who gets the credit even if
+    /// someone else applied the commit (such as with a rebase or a web merge.)
+    pub author: String,
+    /// The resolved unix timestamp of the commit.
+    pub timestamp: i64,
+    /// The timezone offset of the timestamp in seconds east of UTC.
+    pub offset: i32,
+    /// The timezone offset of the author in seconds east of UTC, which is their local time even
+    /// when the timestamp is the committer time.
+    pub author_offset: i32,
+    /// The (weighted) amount of lines removed by the commit.
+    pub lines_removed: f32,
+    /// The (weighted) amount of lines added by the commit.
+    pub lines_added: f32,
+    /// The seconds spent on the commit.
+    pub seconds: u64,
+    /// If the seconds were projected by an [`Estimator`] instead of measured from the previous commit.
+    pub estimated: bool,
+    /// The branches that contain the commit.
+    pub branches: Vec<String>,
+}
+
+/// A stretch of work where no two commits are further apart than the session window.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WorkSession {
+    /// The (projected) unix timestamp the session started at.
+    pub start: i64,
+    /// The unix timestamp of the last commit of the session.
+    pub end: i64,
+    /// The identity of whoever made the most commits in the session.
+    pub author: String,
+    /// The commits of the session, newest first.
+    pub commits: Vec<SessionCommit>,
+    /// The branches that contain any commit of the session.
+    pub branches: Vec<String>,
+    /// The label of the repository the session is in (set by [`crate::report::StatsBuilder`].)
+    pub repo: String,
+    /// The seconds projected before the first commit of the session.
+    pub estimated_seconds: u64,
+    /// The seconds measured between the commits of the session.
+    pub observed_seconds: u64,
+    /// The fraction of the session that was measured instead of projected, from 0 to 1.
+    pub confidence: f32,
+}
+
+impl WorkSession {
+    /// Gets the total amount of seconds of the session.
+    pub fn duration(&self) -> u64 {
+        return self.estimated_seconds + self.observed_seconds;
+    }
+}
+
+/// A commit waiting to be put into a session.
+struct PendingCommit {
+    commit: SessionCommit,
+    author: CommitAuthor,
+    /// The seconds since the previous commit.
+    interval: i64,
+    /// If there is no previous commit to measure from (such as the root commit.)
+    unmeasured: bool,
+}
+
+impl PendingCommit {
+    fn sample(&self) -> EstimatorSample {
+        return EstimatorSample::new(
+            self.commit.lines_removed,
+            self.commit.lines_added,
+            self.interval,
+            self.commit.author.clone(),
+        );
+    }
+}
+
+/// Groups commits into [`WorkSession`]s.
+/// Commits are pushed in the order they are walked (newest first) and each commit is measured from
+/// the next commit pushed, so commits of several branches make up one timeline. When the oldest
+/// commit pushed isn't the root commit and its parent is within the window, its session is taken
+/// to have started before the walk was cut short so its time is measured instead of projected.
+/// ```
+/// # use git_stats::estimate::FixedEstimator;
+/// # use git_stats::objects::commit::CommitObject;
+/// # use git_stats::session::SessionBuilder;
+/// # use git_stats::timestamps::{resolve_time, TimestampSource};
+/// let commit = |timestamp: u64, parent: &str| CommitObject::from_str(&format!("
+/// tree some_big_hash
+/// {parent}author MT <some@email.tld> {timestamp} -0123
+/// committer MT <some@email.tld> {timestamp} -0123
+///
+/// Some message
+/// ").trim(), 999, format!("{timestamp}")).unwrap();
+///
+/// let commits = [
+///     commit(100000, "parent 40000\n"),
+///     commit(40000, "parent 39000\n"),
+///     commit(39000, ""),
+/// ];
+///
+/// let mut builder = SessionBuilder::new(Box::new(FixedEstimator(1800))).window(3600);
+/// for (i, v) in commits.iter().enumerate() {
+///     let time = resolve_time(v, commits.get(i + 1), TimestampSource::Committer, 200000);
+///     builder.push(v, &time, 0.0, 10.0, vec![]);
+/// }
+/// let (sessions, dropped) = builder.build();
+///
+/// assert_eq!(sessions.len(), 2);
+/// assert_eq!(sessions[0].duration(), 1800);
+/// assert_eq!(sessions[0].confidence, 0.0);
+/// assert_eq!(sessions[1].start, 39000 - 1800);
+/// assert_eq!(sessions[1].observed_seconds, 1000);
+/// assert_eq!(sessions[1].commits.len(), 2);
+/// // The gap between the sessions isn't used as a rate
+/// assert_eq!(dropped.outside_window, 1);
+/// ```
+pub struct SessionBuilder {
+    estimator: Box<dyn Estimator>,
+    fallback: Box<dyn Estimator>,
+    per_author: bool,
+    window: u64,
+    author_windows: Vec<(String, u64)>,
+    commits: Vec<PendingCommit>,
+}
+
+impl SessionBuilder {
+    /// Creates a builder that projects session starts with `estimator`.
+    pub fn new(estimator: Box<dyn Estimator>) -> Self {
+        return Self {
+            estimator,
+            fallback: Box::new(FixedEstimator(DEFAULT_FALLBACK)),
+            per_author: false,
+            window: DEFAULT_WINDOW,
+            author_windows: vec![],
+            commits: vec![],
+        };
+    }
+
+    /// Sets how the start of sessions is projected when the estimator has nothing to project from
+    /// (see [`Estimator::has_data`]), [`DEFAULT_FALLBACK`] seconds by default.
+    /// ```
+    /// # use git_stats::estimate::{AverageEstimator, FixedEstimator};
+    /// # use git_stats::objects::commit::CommitObject;
+    /// # use git_stats::session::SessionBuilder;
+    /// # use git_stats::timestamps::{resolve_time, TimestampSource};
+    /// let commit = |timestamp: u64, parent: &str| CommitObject::from_str(&format!("
+    /// tree some_big_hash
+    /// {parent}author MT <some@email.tld> {timestamp} -0123
+    /// committer MT <some@email.tld> {timestamp} -0123
+    ///
+    /// Some message
+    /// ").trim(), 999, format!("{timestamp}")).unwrap();
+    ///
+    /// // Every gap is longer than the window, so there are no rates to project from
+    /// let commits = [commit(100000, "parent 40000\n"), commit(40000, "")];
+    ///
+    /// let mut builder = SessionBuilder::new(Box::new(AverageEstimator::default()))
+    ///     .fallback(Box::new(FixedEstimator(900)))
+    ///     .window(3600);
+    /// for (i, v) in commits.iter().enumerate() {
+    ///     let time = resolve_time(v, commits.get(i + 1), TimestampSource::Committer, 200000);
+    ///     builder.push(v, &time, 0.0, 10.0, vec![]);
+    /// }
+    /// let (sessions, dropped) = builder.build();
+    ///
+    /// assert_eq!(sessions[0].duration(), 900);
+    /// assert_eq!(sessions[1].duration(), 900);
+    /// assert!(dropped.projected_without_data);
+    /// ```
+    pub fn fallback(mut self, fallback: Box<dyn Estimator>) -> Self {
+        self.fallback = fallback;
+        return self;
+    }
+
+    /// Sets the seconds between two commits before a new session starts.
+    pub fn window(mut self, window: u64) -> Self {
+        self.window = window;
+        return self;
+    }
+
+    /// Creates separate sessions for every author, measuring each commit from the previous commit
+    /// of the same author instead of its parent.
+    /// ```
+    /// # use git_stats::estimate::FixedEstimator;
+    /// # use git_stats::objects::commit::CommitObject;
+    /// # use git_stats::session::SessionBuilder;
+    /// # use git_stats::timestamps::{resolve_time, TimestampSource};
+    /// let commit = |timestamp: u64, name: &str, parent: &str| CommitObject::from_str(&format!("
+    /// tree some_big_hash
+    /// {parent}author {name} <{name}@email.tld> {timestamp} -0123
+    /// committer {name} <{name}@email.tld> {timestamp} -0123
+    ///
+    /// Some message
+    /// ").trim(), 999, format!("{timestamp}")).unwrap();
+    ///
+    /// let commits = [
+    ///     commit(3000, "a", "parent 2000\n"),
+    ///     commit(2000, "b", "parent 1000\n"),
+    ///     commit(1000, "a", ""),
+    /// ];
+    ///
+    /// let mut builder = SessionBuilder::new(Box::new(FixedEstimator(600))).per_author(true);
+    /// for (i, v) in commits.iter().enumerate() {
+    ///     let time = resolve_time(v, commits.get(i + 1), TimestampSource::Committer, 200000);
+    ///     builder.push(v, &time, 0.0, 10.0, vec![]);
+    /// }
+    /// let (sessions, _) = builder.build();
+    ///
+    /// assert_eq!(sessions.len(), 2);
+    /// assert_eq!(sessions[0].author, "a@email.tld");
+    /// assert_eq!(sessions[0].observed_seconds, 2000);
+    /// assert_eq!(sessions[1].author, "b@email.tld");
+    /// assert_eq!(sessions[1].estimated_seconds, 600);
+    /// ```
+    pub fn per_author(mut self, per_author: bool) -> Self {
+        self.per_author = per_author;
+        return self;
+    }
+
+    /// Overrides the window for an author by email or name, later overrides win.
+    pub fn author_window(mut self, author: &str, window: u64) -> Self {
+        self.author_windows.push((author.to_string(), window));
+        return self;
+    }
+
+    /// Gets the session window for an author, using the last override matching their email or name.
+    pub fn window_for(&self, author: &CommitAuthor) -> u64 {
+        return self.author_windows
+            .iter()
+            .rev()
+            .find(|(key, _)| key == &author.name || Some(key) == author.email.as_ref())
+            .map(|(_, window)| *window)
+            .unwrap_or(self.window);
+    }
+
+    /// Adds the next (older) commit with its resolved time, (weighted) lines and the branches that
+    /// contain it.
+    pub fn push(&mut self, commit: &CommitObject, time: &CommitTime, lines_removed: f32, lines_added: f32, branches: Vec<String>) {
+        self.commits.push(PendingCommit {
+            commit: SessionCommit {
+                oid: commit.oid.clone(),
+                title: commit.message.trim().to_string(),
+                author: commit.author.identity().to_string(),
+                timestamp: time.timestamp,
+                offset: time.offset,
+                author_offset: commit.author.offset,
+                lines_removed,
+                lines_added,
+                seconds: time.delta.max(0) as u64,
+                estimated: false,
+                branches,
+            },
+            author: commit.author.clone(),
+            interval: time.delta,
+            unmeasured: commit.parent.is_none(),
+        });
+    }
+
+    /// Fits the estimator to the pushed commits and splits them into sessions, newest first.
+    /// Also returns the commits that were left out of the rate statistics of the estimator.
+    pub fn build(mut self) -> (Vec<WorkSession>, DroppedSamples) {
+        let commits = std::mem::take(&mut self.commits);
+        let mut groups = match self.per_author {
+            true => Self::group_by_author(commits),
+            false => vec![commits],
+        };
+        for group in groups.iter_mut() {
+            Self::measure(group, self.per_author);
+        }
+
+        // Commits without a previous commit have nothing to measure their time against and
+        // intervals longer than the window are idle time between sessions
+        let mut outside_window = 0;
+        let samples = groups
+            .iter()
+            .flatten()
+            .filter(|v| !v.unmeasured)
+            .filter(|v| {
+                let is_outside = v.interval > self.window_for(&v.author) as i64;
+                outside_window += is_outside as usize;
+                return !is_outside;
+            })
+            .map(|v| v.sample())
+            .collect::<Vec<EstimatorSample>>();
+        let mut dropped = self.estimator.fit(&samples);
+        dropped.outside_window += outside_window;
+        let estimator = match self.estimator.has_data() {
+            true => &self.estimator,
+            false => {
+                self.fallback.fit(&samples);
+                dropped.projected_without_data = groups.iter().any(|v| !v.is_empty());
+                &self.fallback
+            },
+        };
+        if dropped.total() > 0 {
+            log::warn!(
+                "Left samples out of rate statistics: {} with zero or negative intervals, {} longer than the window, {} not finite, {} trimmed as outliers.",
+                dropped.non_positive_interval,
+                dropped.outside_window,
+                dropped.non_finite,
+                dropped.trimmed,
+            );
+        }
+
+        let mut sessions = groups
+            .iter()
+            .flat_map(|group| group.split_inclusive(|v| v.interval > self.window_for(&v.author) as i64))
+            .map(|pending| {
+                let mut commits = pending
+                    .iter()
+                    .map(|v| v.commit.clone())
+                    .collect::<Vec<SessionCommit>>();
+
+                // The first commit of the session gets a projected amount of time, unless the
+                // walk was cut short and its parent is still close enough to measure from
+                let start = pending.last().unwrap();
+                if start.unmeasured || start.interval > self.window_for(&start.author) as i64 {
+                    let first = commits.last_mut().unwrap();
+                    first.seconds = estimator.estimate(&start.sample());
+                    first.estimated = true;
+                }
+
+                return Self::session(commits);
+            })
+            .collect::<Vec<WorkSession>>();
+
+        if self.per_author {
+            sessions.sort_by_key(|v| std::cmp::Reverse(v.end));
+        }
+        return (sessions, dropped);
+    }
+
+    /// Splits commits by author, keeping their order.
+    fn group_by_author(commits: Vec<PendingCommit>) -> Vec<Vec<PendingCommit>> {
+        let mut groups: Vec<Vec<PendingCommit>> = vec![];
+        for commit in commits {
+            match groups.iter_mut().find(|v| v[0].commit.author == commit.commit.author) {
+                Some(group) => group.push(commit),
+                None => groups.push(vec![commit]),
+            }
+        }
+
+        return groups;
+    }
+
+    /// Measures every commit from the next (older) commit of the group. The oldest commit keeps
+    /// the interval to its parent unless `unmeasured` is set.
+    fn measure(group: &mut [PendingCommit], unmeasured: bool) {
+        for i in 0..group.len() {
+            match group.get(i + 1).map(|v| v.commit.timestamp) {
+                Some(previous) => group[i].interval = group[i].commit.timestamp - previous,
+                None => group[i].unmeasured |= unmeasured,
+            }
+            group[i].commit.seconds = group[i].interval.max(0) as u64;
+        }
+    }
+
+    fn session(commits: Vec<SessionCommit>) -> WorkSession {
+        let first = commits.last().unwrap();
+        let estimated_seconds = commits.iter().filter(|v| v.estimated).map(|v| v.seconds).sum::<u64>();
+        let observed_seconds = commits.iter().filter(|v| !v.estimated).map(|v| v.seconds).sum::<u64>();
+
+        // Counted from the first commit so ties go to whoever started the session
+        let mut authors: Vec<(&str, usize)> = vec![];
+        for commit in commits.iter().rev() {
+            match authors.iter_mut().find(|(author, _)| *author == commit.author) {
+                Some(v) => v.1 += 1,
+                None => authors.push((&commit.author, 1)),
+            }
+        }
+        let author = authors
+            .iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map(|(author, _)| author.to_string())
+            .unwrap_or_default();
+
+        let mut branches = commits
+            .iter()
+            .flat_map(|v| v.branches.iter().cloned())
+            .collect::<Vec<String>>();
+        branches.sort();
+        branches.dedup();
+
+        let duration = estimated_seconds + observed_seconds;
+        let confidence = match duration {
+            0 => 0.0,
+            _ => observed_seconds as f32 / duration as f32,
+        };
+
+        return WorkSession {
+            start: first.timestamp - first.seconds as i64,
+            end: commits[0].timestamp,
+            author,
+            estimated_seconds,
+            observed_seconds,
+            confidence,
+            commits,
+            branches,
+            repo: String::new(),
+        };
+    }
+}
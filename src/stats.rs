@@ -0,0 +1,155 @@
+use std::ops::AddAssign;
+
+use serde::{Deserialize, Serialize};
+
+/// The default fraction of samples trimmed from each end when taking a trimmed mean.
+pub const DEFAULT_TRIM: f32 = 0.1;
+
+/// The samples that were left out of a [`RateStatistics`] and why.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DroppedSamples {
+    /// Samples with an interval of zero or less seconds (such as commits with the same timestamp.)
+    pub non_positive_interval: usize,
+    /// Samples with an interval longer than the session window, which is idle time between
+    /// sessions instead of time spent on the commit.
+    pub outside_window: usize,
+    /// Samples with a rate that isn't a finite number.
+    pub non_finite: usize,
+    /// Samples trimmed from either end as outliers (only left out of the mean.)
+    pub trimmed: usize,
+    /// Whether no samples were left to project session starts from, so they were projected with
+    /// the fallback estimator instead (see [`crate::session::SessionBuilder::fallback`].)
+    pub projected_without_data: bool,
+}
+
+impl DroppedSamples {
+    /// Gets the total amount of dropped samples.
+    pub fn total(&self) -> usize {
+        return self.non_positive_interval + self.outside_window + self.non_finite + self.trimmed;
+    }
+}
+
+impl AddAssign for DroppedSamples {
+    fn add_assign(&mut self, other: Self) {
+        self.non_positive_interval += other.non_positive_interval;
+        self.outside_window += other.outside_window;
+        self.non_finite += other.non_finite;
+        self.trimmed += other.trimmed;
+        self.projected_without_data |= other.projected_without_data;
+    }
+}
+
+/// Statistics about rates (an amount per second) that can't be poisoned by zero length intervals
+/// or skewed by a few huge samples.
+/// ```
+/// # use git_stats::stats::RateStatistics;
+/// // Lines and the seconds it took to write them
+/// let rates = RateStatistics::new([
+///     (10.0, 10), (20.0, 10), (30.0, 10), (40.0, 10), (50.0, 10),
+///     (60.0, 10), (70.0, 10), (80.0, 10), (90.0, 10), (90000.0, 10),
+///     (5.0, 0),
+/// ], 0.1);
+///
+/// assert_eq!(rates.dropped.non_positive_interval, 1);
+/// assert_eq!(rates.dropped.trimmed, 2);
+/// assert_eq!(rates.mean(), 5.5);
+/// assert_eq!(rates.median(), 5.5);
+/// assert_eq!(rates.quantile(0.0), 1.0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RateStatistics {
+    /// The valid rates, sorted.
+    rates: Vec<f32>,
+    /// The amount of rates trimmed from each end for the mean.
+    trim_count: usize,
+    /// The samples that were left out.
+    pub dropped: DroppedSamples,
+}
+
+impl RateStatistics {
+    /// Creates statistics from `(amount, seconds)` samples.
+    /// `trim` is the fraction of samples left out of the mean from each end.
+    pub fn new(samples: impl IntoIterator<Item = (f32, i64)>, trim: f32) -> Self {
+        let mut dropped = DroppedSamples::default();
+        let mut rates: Vec<f32> = vec![];
+
+        for (amount, seconds) in samples {
+            if seconds <= 0 {
+                dropped.non_positive_interval += 1;
+                continue;
+            }
+
+            let rate = amount / seconds as f32;
+            if !rate.is_finite() {
+                dropped.non_finite += 1;
+                continue;
+            }
+            rates.push(rate);
+        }
+
+        rates.sort_by(|a, b| a.total_cmp(b));
+
+        let trim_count = (rates.len() as f32 * trim.clamp(0.0, 0.5)) as usize;
+        let trim_count = trim_count.min(rates.len().saturating_sub(1) / 2);
+        dropped.trimmed = trim_count * 2;
+
+        return Self {
+            rates,
+            trim_count,
+            dropped,
+        };
+    }
+
+    /// Gets the amount of valid rates (including the ones trimmed from the mean.)
+    pub fn count(&self) -> usize {
+        return self.rates.len();
+    }
+
+    /// Gets the trimmed mean of the rates, 0 if there are none.
+    pub fn mean(&self) -> f32 {
+        let kept = &self.rates[self.trim_count..self.rates.len() - self.trim_count];
+        if kept.is_empty() {
+            return 0.0;
+        }
+        return kept.iter().sum::<f32>() / kept.len() as f32;
+    }
+
+    /// Gets the `q` quantile (from 0 to 1) of the rates with linear interpolation, 0 if there are
+    /// none.
+    pub fn quantile(&self, q: f32) -> f32 {
+        if self.rates.is_empty() {
+            return 0.0;
+        }
+
+        let position = q.clamp(0.0, 1.0) * (self.rates.len() - 1) as f32;
+        let lower = position.floor() as usize;
+        let upper = position.ceil() as usize;
+        let fraction = position - lower as f32;
+        return self.rates[lower] + (self.rates[upper] - self.rates[lower]) * fraction;
+    }
+
+    /// Gets the median of the rates, 0 if there are none.
+    pub fn median(&self) -> f32 {
+        return self.quantile(0.5);
+    }
+}
+
+/// Gets the amount of seconds covered by `(start, end)` spans, counting overlapping time once.
+/// ```
+/// # use git_stats::stats::covered_seconds;
+/// assert_eq!(covered_seconds(vec![(0, 100), (50, 150), (200, 300)]), 250);
+/// ```
+pub fn covered_seconds(mut spans: Vec<(i64, i64)>) -> u64 {
+    spans.sort();
+
+    let mut total = 0;
+    let mut covered_until = i64::MIN;
+    for (start, end) in spans {
+        let start = start.max(covered_until);
+        if end > start {
+            total += (end - start) as u64;
+        }
+        covered_until = covered_until.max(end);
+    }
+    return total;
+}
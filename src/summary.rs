@@ -0,0 +1,148 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::{Datelike, Months, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::{report::OutputValue, session::WorkSession, stats::covered_seconds};
+
+/// The length of time the totals of a [`PeriodSummary`] cover.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    /// A calendar day.
+    Day,
+    /// An ISO week, starting on monday.
+    Week,
+    /// A calendar month.
+    Month,
+}
+
+impl Period {
+    /// Gets the first day of the period containing a date.
+    /// ```
+    /// # use git_stats::summary::Period;
+    /// # use chrono::NaiveDate;
+    /// let date = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+    /// assert_eq!(Period::Week.start(date), NaiveDate::from_ymd_opt(2024, 5, 13).unwrap());
+    /// assert_eq!(Period::Month.start(date), NaiveDate::from_ymd_opt(2024, 5, 1).unwrap());
+    /// ```
+    pub fn start(&self, date: NaiveDate) -> NaiveDate {
+        return match self {
+            Period::Day => date,
+            Period::Week => date.week(chrono::Weekday::Mon).first_day(),
+            Period::Month => date.with_day(1).unwrap(),
+        };
+    }
+
+    /// Gets the last day of the period containing a date.
+    pub fn end(&self, date: NaiveDate) -> NaiveDate {
+        return match self {
+            Period::Day => date,
+            Period::Week => date.week(chrono::Weekday::Mon).last_day(),
+            Period::Month => self.start(date) + Months::new(1) - chrono::Days::new(1),
+        };
+    }
+
+    /// Gets the name of the period containing a date (such as `2024-05-15`, `2024-W20` or
+    /// `2024-05`.)
+    /// ```
+    /// # use git_stats::summary::Period;
+    /// # use chrono::NaiveDate;
+    /// let date = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+    /// assert_eq!(Period::Week.name(date), "2024-W20");
+    /// ```
+    pub fn name(&self, date: NaiveDate) -> String {
+        return match self {
+            Period::Day => date.format("%Y-%m-%d").to_string(),
+            Period::Week => format!("{}-W{:02}", date.iso_week().year(), date.iso_week().week()),
+            Period::Month => date.format("%Y-%m").to_string(),
+        };
+    }
+}
+
+/// A summary that is still being totaled.
+struct PendingSummary {
+    summary: PeriodSummary,
+    /// The spans of the commits of every author, kept apart so only the overlaps of an author are
+    /// removed.
+    spans: HashMap<String, Vec<(i64, i64)>>,
+    days: BTreeSet<NaiveDate>,
+}
+
+/// The totals of the commits made in a day, week or month.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PeriodSummary {
+    /// The name of the period (see [`Period::name`].)
+    pub period: String,
+    /// The first day of the period.
+    pub start: String,
+    /// The last day of the period.
+    pub end: String,
+    /// The amount of commits.
+    pub commits: usize,
+    /// The seconds spent on the commits, with the overlapping time of an author only counted once.
+    pub seconds: u64,
+    /// The hours spent on the commits.
+    pub hours: f32,
+    /// The lines removed.
+    pub lines_removed: i64,
+    /// The lines added.
+    pub lines_added: i64,
+    /// The amount of days with commits.
+    pub active_days: usize,
+}
+
+impl PeriodSummary {
+    /// Totals the commits of sessions (along with their values in the same order) for every
+    /// period with commits, newest first. Commits count toward the day they were made on in
+    /// `timezone`.
+    pub fn from_sessions<Tz: TimeZone>(
+        sessions: &[WorkSession],
+        values: &[Vec<OutputValue>],
+        period: Period,
+        timezone: &Tz,
+    ) -> Vec<Self> {
+        let mut summaries: BTreeMap<NaiveDate, PendingSummary> = BTreeMap::new();
+
+        for (session, values) in sessions.iter().zip(values) {
+            for (commit, value) in session.commits.iter().zip(values) {
+                let date = match timezone.timestamp_opt(commit.timestamp, 0).single() {
+                    Some(v) => v.date_naive(),
+                    None => continue,
+                };
+
+                let pending = summaries.entry(period.start(date)).or_insert_with(|| PendingSummary {
+                    summary: Self {
+                        period: period.name(date),
+                        start: period.start(date).to_string(),
+                        end: period.end(date).to_string(),
+                        ..Default::default()
+                    },
+                    spans: HashMap::new(),
+                    days: BTreeSet::new(),
+                });
+
+                pending.summary.commits += 1;
+                pending.summary.lines_removed += value.lines_removed as i64;
+                pending.summary.lines_added += value.lines_added as i64;
+                pending.spans
+                    .entry(commit.author.clone())
+                    .or_default()
+                    .push((commit.timestamp - commit.seconds as i64, commit.timestamp));
+                pending.days.insert(date);
+            }
+        }
+
+        return summaries
+            .into_values()
+            .rev()
+            .map(|pending| {
+                let mut summary = pending.summary;
+                summary.seconds = pending.spans.into_values().map(covered_seconds).sum();
+                summary.hours = summary.seconds as f32 / 3600.0;
+                summary.active_days = pending.days.len();
+                return summary;
+            })
+            .collect();
+    }
+}
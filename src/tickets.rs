@@ -0,0 +1,140 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{report::OutputValue, session::WorkSession};
+
+/// The patterns used to find tickets when none are set, Jira style keys and GitHub style issues.
+pub const DEFAULT_TICKET_PATTERNS: [&str; 2] = [r"\b[A-Z][A-Z0-9]+-\d+\b", r"#\d+\b"];
+
+/// Finds ticket references (such as `ABC-123` or `#42`) in commit messages and branch names.
+/// ```
+/// # use git_stats::tickets::TicketMatcher;
+/// let matcher = TicketMatcher::new(&[]).unwrap();
+/// assert_eq!(
+///     matcher.find("Fix ABC-12 and #4 (see ABC-12)", &["feature/XY-7-login".into()]),
+///     vec!["#4", "ABC-12", "XY-7"],
+/// );
+/// // After `feature/XY-7-login` is merged into `main`, its commits are still only on the first
+/// // parent line of `feature/XY-7-login`, so they keep its ticket
+/// assert_eq!(matcher.find("Add login form", &["feature/XY-7-login".into()]), vec!["XY-7"]);
+/// // The history of `main` that the branch started from is on the line of `main` as well
+/// assert_eq!(matcher.find("Fix login", &["feature/XY-7-login".into(), "main".into()]), Vec::<String>::new());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TicketMatcher {
+    patterns: Vec<Regex>,
+}
+
+impl TicketMatcher {
+    /// Compiles the ticket regexes, [`DEFAULT_TICKET_PATTERNS`] if there are none.
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        let patterns = match patterns.is_empty() {
+            true => DEFAULT_TICKET_PATTERNS.iter().map(|v| v.to_string()).collect(),
+            false => patterns.to_vec(),
+        };
+
+        return Ok(Self {
+            patterns: patterns
+                .iter()
+                .map(|v| Regex::new(v).with_context(|| format!("Can't parse ticket pattern: '{v}'")))
+                .collect::<Result<Vec<Regex>>>()?,
+        });
+    }
+
+    /// Gets the sorted tickets of a commit from its message and the branches whose first parent line
+    /// it is on. Commits merged into `main` are contained by `main` but aren't on its line, while
+    /// the history a branch started from is on the line of the branch as well as `main`. So the
+    /// branches only count if every one of them has a ticket.
+    pub fn find(&self, message: &str, branches: &[String]) -> Vec<String> {
+        let find = |text: &str| self.patterns
+            .iter()
+            .flat_map(|v| v.find_iter(text).map(|v| v.as_str().to_string()))
+            .collect::<Vec<String>>();
+
+        let mut tickets = find(message).into_iter().collect::<BTreeSet<String>>();
+        let branch_tickets = branches.iter().map(|v| find(v)).collect::<Vec<Vec<String>>>();
+        if branch_tickets.iter().all(|v| !v.is_empty()) {
+            tickets.extend(branch_tickets.into_iter().flatten());
+        }
+        return tickets.into_iter().collect();
+    }
+}
+
+/// The time spent on a ticket, for filling in timesheets.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TicketSummary {
+    /// The ticket, `None` for the sessions without any.
+    pub ticket: Option<String>,
+    /// The amount of sessions with the ticket.
+    pub sessions: usize,
+    /// The amount of commits with the ticket.
+    pub commits: usize,
+    /// The seconds of the sessions with the ticket, split evenly between the tickets of every
+    /// session.
+    pub seconds: u64,
+    /// The hours spent on the ticket.
+    pub hours: f32,
+    /// The seconds spent on the ticket every day (such as `2024-05-18`), the day a session ended
+    /// on in the timezone of its last commit.
+    pub days: BTreeMap<String, u64>,
+}
+
+impl TicketSummary {
+    /// Splits the time of sessions (along with their values in the same order) between their
+    /// tickets, the most seconds first.
+    pub fn from_sessions(sessions: &[WorkSession], values: &[Vec<OutputValue>]) -> Vec<Self> {
+        let mut summaries: HashMap<Option<String>, Self> = HashMap::new();
+
+        for (session, values) in sessions.iter().zip(values) {
+            let tickets = values
+                .iter()
+                .flat_map(|v| v.tickets.iter().cloned())
+                .collect::<BTreeSet<String>>();
+            let tickets = match tickets.is_empty() {
+                true => vec![None],
+                false => tickets.into_iter().map(Some).collect(),
+            };
+
+            let seconds = session.commits.iter().map(|v| v.seconds).sum::<u64>();
+            let day = session.commits
+                .first()
+                .and_then(|v| DateTime::from_timestamp(v.timestamp + v.offset as i64, 0))
+                .map(|v| v.date_naive().to_string())
+                .unwrap_or_default();
+
+            for (i, ticket) in tickets.iter().enumerate() {
+                // The first ticket gets the remainder so the split adds up to the session
+                let share = seconds / tickets.len() as u64 + match i {
+                    0 => seconds % tickets.len() as u64,
+                    _ => 0,
+                };
+
+                let summary = summaries.entry(ticket.clone()).or_insert_with(|| Self {
+                    ticket: ticket.clone(),
+                    ..Default::default()
+                });
+                summary.sessions += 1;
+                summary.commits += values
+                    .iter()
+                    .filter(|v| ticket.as_ref().map_or(v.tickets.is_empty(), |t| v.tickets.contains(t)))
+                    .count();
+                summary.seconds += share;
+                *summary.days.entry(day.clone()).or_default() += share;
+            }
+        }
+
+        let mut summaries = summaries
+            .into_values()
+            .map(|mut v| {
+                v.hours = v.seconds as f32 / 3600.0;
+                return v;
+            })
+            .collect::<Vec<Self>>();
+        summaries.sort_by(|a, b| b.seconds.cmp(&a.seconds).then(a.ticket.cmp(&b.ticket)));
+        return summaries;
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+use crate::objects::commit::{CommitAuthor, CommitObject};
+
+/// How far in the future (in seconds) a commit can be before it is flagged, to allow for small
+/// clock differences between machines.
+pub const FUTURE_TOLERANCE: i64 = 3600;
+
+/// Which timestamp of a commit is used as the time of the commit.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampSource {
+    /// The time the commit was made (changes on rebases and amends.)
+    Committer,
+    /// The time the changes were originally authored.
+    Author,
+    /// Uses the committer time unless it has anomalies that the author time doesn't have.
+    Auto,
+}
+
+/// Something wrong with the timestamp of a commit.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampAnomaly {
+    /// The commit is older than its parent (usually from rebases or a bad clock.)
+    OutOfOrder,
+    /// The commit is dated in the future.
+    FutureDated,
+}
+
+/// The resolved time of a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitTime {
+    /// The timestamp used for the commit.
+    pub timestamp: i64,
+    /// The seconds between the commit and its parent, never negative.
+    pub delta: i64,
+    /// The timezone offset of the timestamp in seconds east of UTC.
+    pub offset: i32,
+    /// Where the timestamp came from, either [`TimestampSource::Committer`] or [`TimestampSource::Author`].
+    pub source: TimestampSource,
+    /// The anomalies of the timestamp that was used.
+    pub anomalies: Vec<TimestampAnomaly>,
+}
+
+fn anomalies_of(time: &CommitAuthor, parent_time: Option<&CommitAuthor>, now: i64) -> Vec<TimestampAnomaly> {
+    let mut anomalies = vec![];
+    if parent_time.is_some_and(|v| v.timestamp > time.timestamp) {
+        anomalies.push(TimestampAnomaly::OutOfOrder);
+    }
+    if time.timestamp as i64 > now + FUTURE_TOLERANCE {
+        anomalies.push(TimestampAnomaly::FutureDated);
+    }
+    return anomalies;
+}
+
+/// Resolves the time of a commit compared to its parent, `now` being the current unix timestamp.
+/// ```
+/// # use git_stats::objects::commit::CommitObject;
+/// # use git_stats::timestamps::{resolve_time, TimestampAnomaly, TimestampSource};
+/// let commit = |author: u64, committer: u64| CommitObject::from_str(&format!("
+/// tree some_big_hash
+/// author MT <some@email.tld> {author} -0123
+/// committer MT <some@email.tld> {committer} -0123
+///
+/// Some message
+/// ").trim(), 999, "some_sha1_hash".into()).unwrap();
+///
+/// // Rebased commit whose committer time is older than its parent
+/// let parent = commit(1000, 5000);
+/// let child = commit(2000, 4000);
+///
+/// let time = resolve_time(&child, Some(&parent), TimestampSource::Committer, 10000);
+/// assert_eq!(time.delta, 0);
+/// assert_eq!(time.anomalies, vec![TimestampAnomaly::OutOfOrder]);
+///
+/// let time = resolve_time(&child, Some(&parent), TimestampSource::Auto, 10000);
+/// assert_eq!(time.source, TimestampSource::Author);
+/// assert_eq!(time.delta, 1000);
+/// assert!(time.anomalies.is_empty());
+/// ```
+pub fn resolve_time(commit: &CommitObject, parent: Option<&CommitObject>, source: TimestampSource, now: i64) -> CommitTime {
+    let committer_anomalies = anomalies_of(&commit.committer, parent.map(|v| &v.committer), now);
+
+    let use_author = match source {
+        TimestampSource::Committer => false,
+        TimestampSource::Author => true,
+        TimestampSource::Auto => {
+            !committer_anomalies.is_empty() &&
+                anomalies_of(&commit.author, parent.map(|v| &v.author), now).is_empty()
+        },
+    };
+
+    let (time, parent_time, source) = match use_author {
+        true => (&commit.author, parent.map(|v| &v.author), TimestampSource::Author),
+        false => (&commit.committer, parent.map(|v| &v.committer), TimestampSource::Committer),
+    };
+
+    let timestamp = time.timestamp as i64;
+    let delta = match parent_time {
+        Some(v) => (timestamp - v.timestamp as i64).max(0),
+        None => 0,
+    };
+
+    return CommitTime {
+        timestamp,
+        delta,
+        offset: time.offset,
+        source,
+        anomalies: anomalies_of(time, parent_time, now),
+    };
+}
@@ -1,17 +1,79 @@
+// The amount of commits requested at a time
+const PAGE_SIZE = 200;
+
+function showError(message) {
+    var errorEl = document.getElementById("error");
+    errorEl.textContent = message;
+    errorEl.hidden = false;
+}
+
 function updateCalendar() {
-    fetch("/api/data")
-        .then(res => res.json())
-        .then(events => {
-            var calendarEl = document.getElementById("calendar");
-            var calendar = new FullCalendar.Calendar(calendarEl, {
-                initialView: "dayGridMonth",
-                headerToolbar: {
-                    "left": "prev,next today",
-                    "center": "title",
-                    "right": "dayGridMonth,timeGridWeek,timeGridDay,listWeek"
-                },
-                events: events
+    var calendarEl = document.getElementById("calendar");
+
+    // The cursor of the next page from the server, null once every commit is loaded
+    var cursor = null;
+    // The start of the oldest event loaded so far of every repo and of all of them
+    var oldestByRepo = {};
+    var oldest = null;
+    var finished = false;
+    var loading = false;
+
+    var calendar = new FullCalendar.Calendar(calendarEl, {
+        initialView: "dayGridMonth",
+        headerToolbar: {
+            "left": "prev,next today",
+            "center": "title",
+            "right": "dayGridMonth,timeGridWeek,timeGridDay,listWeek"
+        },
+        // Loads older pages when the user pages back past the oldest commit
+        datesSet: info => loadUntil(info.start)
+    });
+
+    function loadUntil(date) {
+        if (finished || loading || (oldest !== null && oldest <= date)) {
+            return;
+        }
+        loading = true;
+
+        var url = "/api/data?limit=" + PAGE_SIZE;
+        if (cursor !== null) {
+            url += "&cursor=" + encodeURIComponent(cursor);
+        }
+
+        fetch(url)
+            .then(res => {
+                if (!res.ok) {
+                    return res.text().then(text => Promise.reject(new Error(text)));
+                }
+                // The walk order isn't the order of the events, so the server sends the cursor
+                cursor = res.headers.get("Next-Cursor");
+                return res.json();
+            })
+            .then(events => {
+                loading = false;
+                if (cursor === null) {
+                    finished = true;
+                }
+                if (events.length > 0) {
+                    calendar.addEventSource(events);
+                    events.forEach(event => {
+                        var start = new Date(event.start);
+                        if (!(event.repo in oldestByRepo) || start < oldestByRepo[event.repo]) {
+                            oldestByRepo[event.repo] = start;
+                        }
+                    });
+                    // Repos are paged on their own, so everything is only loaded up to the repo
+                    // that is furthest behind
+                    oldest = new Date(Math.max(...Object.values(oldestByRepo)));
+                }
+                loadUntil(calendar.view.activeStart);
+            })
+            .catch(error => {
+                loading = false;
+                finished = true;
+                showError("Can't load older commits: " + error.message);
             });
-            calendar.render();
-        });
+    }
+
+    calendar.render();
 }
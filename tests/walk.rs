@@ -0,0 +1,164 @@
+use std::{
+    path::PathBuf,
+    process::Command,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use git_stats::{
+    estimate::FixedEstimator,
+    export::FlatRow,
+    heatmap::Heatmap,
+    report::StatsBuilder,
+    timestamps::TimestampSource,
+};
+
+/// A repository created with the git command line in a temporary directory.
+struct TestRepo {
+    dir: PathBuf,
+}
+
+static REPO_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+impl TestRepo {
+    /// Creates an empty repository on `main` in a new directory named `name`.
+    fn new(name: &str) -> Self {
+        let count = REPO_COUNT.fetch_add(1, Ordering::SeqCst);
+        let dir = std::env::temp_dir()
+            .join(format!("git-stats-tests-{}-{count}", std::process::id()))
+            .join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let repo = Self { dir };
+        repo.git(&["init", "-q", "-b", "main"], &[]);
+        return repo;
+    }
+
+    /// Runs git in the repository and gets its trimmed output.
+    fn git(&self, args: &[&str], env: &[(&str, &str)]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&self.dir)
+            .envs(env.iter().copied())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+        return String::from_utf8(output.stdout).unwrap().trim().to_string();
+    }
+
+    /// Commits a new file with the author `a <a@x.tld>` and `c <c@x.tld>` as the committer, with
+    /// dates such as `2024-05-13T10:00:00+0000`.
+    fn commit(&self, message: &str, author_date: &str, committer_date: &str) {
+        let count = std::fs::read_dir(&self.dir).unwrap().count();
+        std::fs::write(self.dir.join(format!("file{count}")), format!("{message}\n")).unwrap();
+        self.git(&["add", "."], &[]);
+        self.git(&["commit", "-q", "-m", message], &[
+            ("GIT_AUTHOR_NAME", "a"),
+            ("GIT_AUTHOR_EMAIL", "a@x.tld"),
+            ("GIT_AUTHOR_DATE", author_date),
+            ("GIT_COMMITTER_NAME", "c"),
+            ("GIT_COMMITTER_EMAIL", "c@x.tld"),
+            ("GIT_COMMITTER_DATE", committer_date),
+        ]);
+    }
+}
+
+/// Gets the titles of the commits of a report, newest session first.
+fn titles(builder: StatsBuilder) -> Vec<String> {
+    return builder
+        .build()
+        .unwrap()
+        .values
+        .into_iter()
+        .flatten()
+        .map(|v| v.title)
+        .collect();
+}
+
+#[test]
+fn since_with_author_time_out_of_order() {
+    let repo = TestRepo::new("since");
+    repo.commit("first", "2024-05-11T10:00:00+0000", "2024-05-11T10:00:00+0000");
+    repo.commit("second", "2024-05-12T10:00:00+0000", "2024-05-12T10:00:00+0000");
+    // Rebased, so the author time is long before the committer time
+    repo.commit("rebased", "2024-05-01T10:00:00+0000", "2024-05-13T10:00:00+0000");
+    repo.commit("third", "2024-05-14T10:00:00+0000", "2024-05-14T10:00:00+0000");
+
+    let since = chrono::DateTime::parse_from_rfc3339("2024-05-10T00:00:00Z").unwrap().timestamp();
+    let builder = StatsBuilder::new(&repo.dir)
+        .timestamp_source(TimestampSource::Author)
+        .since(Some(since));
+
+    let mut titles = titles(builder);
+    titles.sort();
+    assert_eq!(titles, vec!["first", "second", "third"]);
+}
+
+#[test]
+fn heatmap_in_author_timezone() {
+    let repo = TestRepo::new("heatmap");
+    // Monday at 10:00 for the author, applied at the same instant by a committer at 01:00
+    repo.commit("applied", "2024-05-13T10:00:00+0200", "2024-05-13T01:00:00-0700");
+
+    let report = StatsBuilder::new(&repo.dir).build().unwrap();
+    let heatmap = Heatmap::from_sessions(&report.sessions);
+    assert_eq!(heatmap.commits[0][10], 1);
+    assert_eq!(heatmap.commits[0][1], 0);
+}
+
+#[test]
+fn filter_by_author() {
+    let repo = TestRepo::new("filter");
+    repo.commit("applied", "2024-05-13T10:00:00+0000", "2024-05-13T10:00:00+0000");
+
+    let by_email = |email: &str| titles(StatsBuilder::new(&repo.dir).email(Some(email.to_string())));
+    assert_eq!(by_email("a@x.tld"), vec!["applied"]);
+    assert!(by_email("c@x.tld").is_empty());
+
+    let by_name = |name: &str| titles(StatsBuilder::new(&repo.dir).committer(Some(name.to_string())));
+    assert_eq!(by_name("a"), vec!["applied"]);
+    assert!(by_name("c").is_empty());
+}
+
+#[test]
+fn tickets_in_message_body() {
+    let repo = TestRepo::new("tickets");
+    repo.commit("Fix login\n\nCloses #42\nRefs: ABC-123", "2024-05-13T10:00:00+0000", "2024-05-13T10:00:00+0000");
+
+    let report = StatsBuilder::new(&repo.dir).build().unwrap();
+    let commit = &report.values[0][0];
+    assert_eq!(commit.title, "Fix login");
+    assert_eq!(commit.tickets, vec!["#42", "ABC-123"]);
+}
+
+#[test]
+fn session_rows_estimated_by_first_commit() {
+    let repo = TestRepo::new("rows");
+    repo.commit("first", "2024-05-13T10:00:00+0000", "2024-05-13T10:00:00+0000");
+    repo.commit("second", "2024-05-13T10:30:00+0000", "2024-05-13T10:30:00+0000");
+
+    // The first commit is projected even though it gets no time
+    let report = StatsBuilder::new(&repo.dir)
+        .estimator(Box::new(FixedEstimator(0)))
+        .build()
+        .unwrap();
+    let rows = FlatRow::sessions(&report.sessions, &report.values);
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0].estimated);
+}
+
+#[test]
+fn timestamp_out_of_range() {
+    let repo = TestRepo::new("range");
+    repo.commit("first", "2024-05-13T10:00:00+0000", "2024-05-13T10:00:00+0000");
+
+    // Git itself won't make a commit this far in the future, so it is written by hand
+    let tree = repo.git(&["write-tree"], &[]);
+    let parent = repo.git(&["rev-parse", "HEAD"], &[]);
+    let path = repo.dir.join("future.txt");
+    std::fs::write(&path, format!("tree {tree}\nparent {parent}\nauthor a <a@x.tld> 99999999999999 +0000\ncommitter c <c@x.tld> 99999999999999 +0000\n\nfuture\n")).unwrap();
+    let oid = repo.git(&["hash-object", "-t", "commit", "-w", "--literally", path.to_str().unwrap()], &[]);
+    repo.git(&["update-ref", "refs/heads/main", &oid], &[]);
+
+    assert_eq!(titles(StatsBuilder::new(&repo.dir)), vec!["first"]);
+}